@@ -0,0 +1,660 @@
+//! 命令行工具 - 批量生成、求解和校验关卡
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use logic_core::generator::{enumerate_obstacle_layouts, state_from_obstacle_board};
+use logic_core::export::{board_to_png, puzzle_sheet_svg, puzzle_to_svg, solution_to_svg};
+use logic_core::{
+    BoardMask, Difficulty, ExportOptions, GameState, GenerationConstraints, GenerationStrategy,
+    Generator, LevelPack, Orientation, PlacementModel, PuzzleBook, RenderCharset, RenderOptions,
+    SolveResult, Solver, VoidPattern,
+};
+use serde::Serialize;
+
+/// 逻辑拼图命令行工具
+#[derive(Debug, Parser)]
+#[command(name = "logic-puzzle-cli", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// 生成唯一解关卡
+    Generate {
+        /// 难度：easy / medium / hard
+        #[arg(long, default_value = "medium")]
+        difficulty: Difficulty,
+        /// 随机种子（相同种子生成相同关卡）
+        #[arg(long)]
+        seed: Option<u64>,
+        /// 生成数量
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// 输出目录（每关一个JSON文件），不指定则输出到stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// 生成方式：auto / solution / obstacles
+        #[arg(long, default_value = "auto")]
+        strategy: GenerationStrategy,
+        /// 在stderr输出每关的生成统计
+        #[arg(long)]
+        report: bool,
+        #[command(flatten)]
+        constraints: ConstraintArgs,
+        /// 生成带空洞的关卡（方块集合相应减少）
+        #[arg(long, value_enum)]
+        voids: Option<Voids>,
+        /// 棋盘形状：heart / staircase / 形状文件（8行，#为棋盘内，.为棋盘外）
+        #[arg(long, conflicts_with = "voids")]
+        shape: Option<String>,
+    },
+    /// 求解关卡文件
+    Solve {
+        /// 关卡文件（JSON或.txt棋盘图）
+        file: PathBuf,
+        #[command(flatten)]
+        render: RenderArgs,
+    },
+    /// 校验关卡是否有唯一解（非唯一解时返回非零退出码）
+    Validate {
+        /// 关卡文件（JSON或.txt棋盘图）
+        file: PathBuf,
+    },
+    /// 为多解关卡添加最少的固定提示方块，输出唯一解关卡
+    Clues {
+        /// 关卡文件（JSON或.txt棋盘图）
+        file: PathBuf,
+        /// 输出文件，不指定则输出到stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// 以关卡的解为答案，精简出预置方块最少的唯一解关卡
+    Reduce {
+        /// 关卡文件（JSON或.txt棋盘图）
+        file: PathBuf,
+        /// 随机种子（决定贪心阶段的尝试顺序）
+        #[arg(long)]
+        seed: Option<u64>,
+        /// 穷举阶段最多检查多少次唯一性
+        #[arg(long, default_value_t = 10_000)]
+        max_checks: usize,
+        /// 输出文件，不指定则输出到stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// 统计关卡解的数量
+    Count {
+        /// 关卡文件（JSON或.txt棋盘图）
+        file: PathBuf,
+        /// 最多统计多少个解
+        #[arg(long, default_value_t = 1000, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        limit: usize,
+    },
+    /// 在终端中显示关卡
+    Render {
+        /// 关卡文件（JSON或.txt棋盘图）
+        file: PathBuf,
+        #[command(flatten)]
+        render: RenderArgs,
+    },
+    /// 导出关卡为SVG/PNG（用于打印）
+    Export {
+        /// 关卡文件（JSON或.txt棋盘图）
+        file: PathBuf,
+        /// 输出格式
+        #[arg(long, value_enum, default_value = "svg")]
+        format: ExportFormat,
+        /// 导出答案而不是题面
+        #[arg(long)]
+        solution: bool,
+        /// 标出方块ID
+        #[arg(long)]
+        labels: bool,
+        /// 输出文件
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// 导出关卡的SAT/ILP模型（用外部求解器交叉验证）
+    Model {
+        /// 关卡文件（JSON或.txt棋盘图）
+        file: PathBuf,
+        /// 模型格式
+        #[arg(long, value_enum, default_value = "cnf")]
+        format: ModelFormat,
+        /// 排除自带求解器找到的解（外部求解器无解即证明唯一）
+        #[arg(long)]
+        exclude_solution: bool,
+        /// 输出文件
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// 读取外部求解器的结果，还原并校验解
+    Import {
+        /// 关卡文件（JSON或.txt棋盘图）
+        file: PathBuf,
+        /// 求解器输出文件
+        assignment: PathBuf,
+        /// 模型格式（cnf读取v行，lp/mps读取变量取值）
+        #[arg(long, value_enum, default_value = "cnf")]
+        format: ModelFormat,
+    },
+    /// 生成可打印的谜题书（HTML，A4分页，书后附答案）
+    Book {
+        /// 书名（默认为关卡包名称或“逻辑拼图”）
+        #[arg(long)]
+        title: Option<String>,
+        /// 关卡数量
+        #[arg(long, default_value_t = 20)]
+        count: usize,
+        /// 随机种子
+        #[arg(long)]
+        seed: Option<u64>,
+        /// 用已有的关卡包排版，而不是生成新关卡
+        #[arg(long)]
+        pack: Option<PathBuf>,
+        /// 输出文件
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// 枚举障碍布局（每个对称类一个）并统计解的情况
+    Enumerate {
+        /// 作为障碍的方块ID
+        #[arg(long, value_delimiter = ',', default_value = "1,2,3")]
+        pieces: Vec<u8>,
+        /// 最多处理多少个布局
+        #[arg(long)]
+        limit: Option<usize>,
+        /// 只输出唯一解的布局
+        #[arg(long)]
+        unique_only: bool,
+        /// 每个布局最多统计多少个解（大于2时输出中的solutions是精确解数或上限）
+        #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(2..))]
+        max_solutions: u64,
+    },
+}
+
+/// 字符集
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Charset {
+    Ascii,
+    Unicode,
+    Blocks,
+}
+
+/// 空洞图案
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Voids {
+    /// 中心挖掉2×2
+    Donut,
+    /// 四角各挖掉2×2
+    Cross,
+    /// 随机对称空洞
+    Random,
+}
+
+impl From<Voids> for VoidPattern {
+    fn from(voids: Voids) -> Self {
+        match voids {
+            Voids::Donut => VoidPattern::Donut,
+            Voids::Cross => VoidPattern::Cross,
+            Voids::Random => VoidPattern::Random,
+        }
+    }
+}
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    /// 棋盘SVG
+    Svg,
+    /// 棋盘PNG
+    Png,
+    /// A4题目页SVG（含方块托盘）
+    Sheet,
+}
+
+/// 模型格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ModelFormat {
+    /// DIMACS CNF
+    Cnf,
+    /// CPLEX LP
+    Lp,
+    /// 自由格式MPS
+    Mps,
+}
+
+/// 棋盘渲染参数
+#[derive(Debug, Args)]
+struct RenderArgs {
+    /// 字符集
+    #[arg(long, value_enum, default_value = "unicode")]
+    charset: Charset,
+    /// 使用24位ANSI颜色
+    #[arg(long)]
+    color: bool,
+    /// 画出方块边框
+    #[arg(long)]
+    outline: bool,
+    /// 显示行列坐标
+    #[arg(long)]
+    coords: bool,
+}
+
+impl RenderArgs {
+    fn options(&self) -> RenderOptions {
+        RenderOptions {
+            charset: match self.charset {
+                Charset::Ascii => RenderCharset::Ascii,
+                Charset::Unicode => RenderCharset::Unicode,
+                Charset::Blocks => RenderCharset::Blocks,
+            },
+            ansi_color: self.color,
+            outlines: self.outline,
+            coordinates: self.coords,
+        }
+    }
+}
+
+/// 关卡生成约束参数
+#[derive(Debug, Args)]
+struct ConstraintArgs {
+    /// 每个障碍都贴着棋盘边缘
+    #[arg(long)]
+    border_obstacles: bool,
+    /// 禁止障碍出现的区域：行,列,高,宽（可重复）
+    #[arg(long, value_name = "R,C,H,W", num_args = 1, value_delimiter = ',', action = clap::ArgAction::Append)]
+    no_obstacles_in: Vec<usize>,
+    /// 解中必须占据一个角的方块ID（可重复）
+    #[arg(long, value_name = "ID")]
+    corner: Vec<u8>,
+    /// 解中必须竖放的方块ID（可重复）
+    #[arg(long, value_name = "ID")]
+    vertical: Vec<u8>,
+    /// 解中必须横放的方块ID（可重复）
+    #[arg(long, value_name = "ID")]
+    horizontal: Vec<u8>,
+}
+
+impl ConstraintArgs {
+    fn constraints(&self) -> Result<GenerationConstraints, String> {
+        if self.no_obstacles_in.len() % 4 != 0 {
+            return Err("--no-obstacles-in expects row,col,height,width".to_string());
+        }
+        let mut constraints = GenerationConstraints::new();
+        if self.border_obstacles {
+            constraints = constraints.obstacles_touch_border();
+        }
+        for region in self.no_obstacles_in.chunks(4) {
+            constraints = constraints.no_obstacles_in(region[0], region[1], region[2], region[3]);
+        }
+        for &id in &self.corner {
+            constraints = constraints.piece_in_corner(id);
+        }
+        for &id in &self.vertical {
+            constraints = constraints.piece_orientation(id, Orientation::Vertical);
+        }
+        for &id in &self.horizontal {
+            constraints = constraints.piece_orientation(id, Orientation::Horizontal);
+        }
+        Ok(constraints)
+    }
+}
+
+/// 求解结果（JSON输出）
+#[derive(Debug, Serialize)]
+struct SolveOutput {
+    status: &'static str,
+    solutions: Vec<logic_core::Solution>,
+}
+
+/// 枚举结果（每行一个JSON）
+#[derive(Debug, Serialize)]
+struct EnumerateOutput {
+    status: &'static str,
+    solutions: usize,
+    level: GameState,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<ExitCode, String> {
+    match command {
+        Command::Generate { difficulty, seed, count, out, strategy, report, constraints, voids, shape } => {
+            let shape = shape.as_deref().map(parse_shape).transpose()?;
+            let generator = match seed {
+                Some(seed) => Generator::with_seed(seed),
+                None => Generator::new(),
+            };
+            let constraints = constraints.constraints()?;
+            constraints.validate(&difficulty.get_obstacle_piece_ids()).map_err(|e| e.to_string())?;
+            let generator = generator.with_constraints(constraints).map_err(|e| e.to_string())?;
+
+            let mut levels = Vec::with_capacity(count);
+            for i in 0..count {
+                let outcome = match (voids, &shape) {
+                    (Some(voids), _) => generator.generate_with_voids_report(difficulty, voids.into(), strategy),
+                    (None, Some(mask)) => generator.generate_with_mask_report(difficulty, mask, strategy),
+                    (None, None) => generator.generate_with_report(difficulty, strategy),
+                };
+                if report || outcome.level.is_none() {
+                    eprintln!("{}", serde_json::to_string(&outcome.report).map_err(|e| e.to_string())?);
+                }
+                let level = outcome.level.ok_or_else(|| format!("Failed to generate level {}", i + 1))?;
+                levels.push(level);
+            }
+
+            match out {
+                Some(dir) => {
+                    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+                    for (i, level) in levels.iter().enumerate() {
+                        let path = dir.join(format!("level-{:03}.json", i + 1));
+                        write_level(&path, level)?;
+                        println!("{}", path.display());
+                    }
+                }
+                None => println!("{}", to_json(&levels)?),
+            }
+        }
+        Command::Solve { file, render } => {
+            let level = read_level(&file)?;
+            let output = match Solver::new(2).solve(&level) {
+                SolveResult::NoSolution => SolveOutput { status: "no_solution", solutions: Vec::new() },
+                SolveResult::UniqueSolution(s) => SolveOutput { status: "unique", solutions: vec![s] },
+                SolveResult::MultipleSolutions(s) => SolveOutput { status: "multiple", solutions: s },
+            };
+
+            if let Some(solution) = output.solutions.first() {
+                eprint!("{}", solution.board.render(&render.options()));
+            }
+            println!("{}", to_json(&output)?);
+        }
+        Command::Validate { file } => {
+            let level = read_level(&file)?;
+            let (status, ok) = match Solver::new(2).solve(&level) {
+                SolveResult::NoSolution => ("no_solution", false),
+                SolveResult::UniqueSolution(_) => ("unique", true),
+                SolveResult::MultipleSolutions(_) => ("multiple", false),
+            };
+
+            println!("{}: {}", file.display(), status);
+            if !ok {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Clues { file, out } => {
+            let level = read_level(&file)?;
+            let level = Generator::new()
+                .add_minimal_clues(&level)
+                .ok_or_else(|| format!("{}: level has no solution or the clue search gave up", file.display()))?;
+            eprintln!("{}: {} clue(s)", file.display(), level.clues.len());
+            match out {
+                Some(path) => write_level(&path, &level)?,
+                None => println!("{}", to_json(&level)?),
+            }
+        }
+        Command::Reduce { file, seed, max_checks, out } => {
+            let level = read_level(&file)?;
+            let mut solution = match Solver::new(1).solve(&level) {
+                SolveResult::UniqueSolution(solution) => solution,
+                SolveResult::MultipleSolutions(mut solutions) => solutions.remove(0),
+                SolveResult::NoSolution => return Err(format!("{}: level has no solution", file.display())),
+            };
+            // 关卡中的障碍也是解的一部分，可以被精简掉
+            solution.placements.extend(level.obstacle_placements());
+
+            let generator = match seed {
+                Some(seed) => Generator::with_seed(seed),
+                None => Generator::new(),
+            };
+            let reduction = generator.reduce_solution(&solution, max_checks);
+            eprintln!(
+                "{}: {} given(s) {:?}, {}",
+                file.display(),
+                reduction.givens.len(),
+                reduction.givens,
+                if reduction.provably_minimal { "minimal" } else { "not proven minimal" }
+            );
+            match out {
+                Some(path) => write_level(&path, &reduction.level)?,
+                None => println!("{}", to_json(&reduction.level)?),
+            }
+        }
+        Command::Count { file, limit } => {
+            let level = read_level(&file)?;
+            let count = Solver::new(limit).count_solutions(&level);
+            if count >= limit {
+                println!("{}+", count);
+            } else {
+                println!("{}", count);
+            }
+        }
+        Command::Render { file, render } => {
+            print!("{}", read_level(&file)?.board.render(&render.options()));
+        }
+        Command::Export { file, format, solution, labels, out } => {
+            let level = read_level(&file)?;
+            let options = ExportOptions { labels, ..ExportOptions::default() };
+            let solved = if solution { Some(solve_unique(&level)?) } else { None };
+
+            let bytes = match (format, &solved) {
+                (ExportFormat::Svg, Some(solution)) => solution_to_svg(solution, &options).into_bytes(),
+                (ExportFormat::Svg, None) => puzzle_to_svg(&level, &options).into_bytes(),
+                (ExportFormat::Png, Some(solution)) => board_to_png(&solution.board, &options)?,
+                (ExportFormat::Png, None) => board_to_png(&level.board, &options)?,
+                (ExportFormat::Sheet, _) => {
+                    let title = file.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+                    puzzle_sheet_svg(&level, &title, &ExportOptions { cell_size: 12, ..options }).into_bytes()
+                }
+            };
+            fs::write(&out, bytes).map_err(|e| format!("{}: {}", out.display(), e))?;
+            println!("{}", out.display());
+        }
+        Command::Model { file, format, exclude_solution, out } => {
+            let level = read_level(&file)?;
+            let mut model = PlacementModel::new(&level);
+            if exclude_solution {
+                model.exclude(&solve_unique(&level)?).map_err(|e| e.to_string())?;
+            }
+
+            let text = match format {
+                ModelFormat::Cnf => model.to_dimacs(),
+                ModelFormat::Lp => model.to_lp(),
+                ModelFormat::Mps => model.to_mps(),
+            };
+            fs::write(&out, text).map_err(|e| format!("{}: {}", out.display(), e))?;
+            eprintln!("{}: {} variable(s)", file.display(), model.variable_count());
+            println!("{}", out.display());
+        }
+        Command::Import { file, assignment, format } => {
+            let level = read_level(&file)?;
+            let text = fs::read_to_string(&assignment).map_err(|e| format!("{}: {}", assignment.display(), e))?;
+            let model = PlacementModel::new(&level);
+            let solution = match format {
+                ModelFormat::Cnf => model.solution_from_dimacs(&level, &text),
+                ModelFormat::Lp | ModelFormat::Mps => model.solution_from_values(&level, &text),
+            }
+            .map_err(|e| format!("{}: {}", assignment.display(), e))?;
+
+            eprint!("{}", solution.board);
+            println!("{}", to_json(&solution)?);
+        }
+        Command::Book { title, count, seed, pack, out } => {
+            let mut book = match pack {
+                Some(path) => {
+                    let json = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                    let pack = LevelPack::from_json(&json).map_err(|e| format!("{}: {}", path.display(), e))?;
+                    PuzzleBook::from_pack(&pack)?
+                }
+                None => {
+                    let generator = match seed {
+                        Some(seed) => Generator::with_seed(seed),
+                        None => Generator::new(),
+                    };
+                    generator
+                        .generate_book("逻辑拼图", count)
+                        .ok_or_else(|| "Failed to generate puzzle book".to_string())?
+                }
+            };
+            if let Some(title) = title {
+                book.title = title;
+            }
+
+            let html = book.to_html(&ExportOptions { cell_size: 12, ..ExportOptions::default() });
+            fs::write(&out, html).map_err(|e| format!("{}: {}", out.display(), e))?;
+            println!("{} ({} pages)", out.display(), book.page_count());
+        }
+        Command::Enumerate { pieces, limit, unique_only, max_solutions } => {
+            // 穷举计数时不同放置顺序会反复到达相同局面，用置换表避免重复搜索
+            let solver = Solver::new(max_solutions as usize).with_memo(true);
+            let layouts = enumerate_obstacle_layouts(&pieces);
+            eprintln!("{} layouts", layouts.len());
+
+            for board in layouts.into_iter().take(limit.unwrap_or(usize::MAX)) {
+                let level = state_from_obstacle_board(board);
+                let solutions = solver.count_solutions(&level);
+                let status = match solutions {
+                    0 => "no_solution",
+                    1 => "unique",
+                    _ => "multiple",
+                };
+                if unique_only && status != "unique" {
+                    continue;
+                }
+                println!("{}", serde_json::to_string(&EnumerateOutput { status, solutions, level }).map_err(|e| e.to_string())?);
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 解析棋盘形状（内置名称或形状文件）
+fn parse_shape(shape: &str) -> Result<BoardMask, String> {
+    match shape {
+        "heart" => Ok(BoardMask::heart()),
+        "staircase" => Ok(BoardMask::staircase()),
+        path => {
+            let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+            text.parse().map_err(|e| format!("{}: {}", path, e))
+        }
+    }
+}
+
+/// 求出关卡的唯一解
+fn solve_unique(level: &GameState) -> Result<logic_core::Solution, String> {
+    match Solver::new(2).solve(level) {
+        SolveResult::UniqueSolution(solution) => Ok(solution),
+        SolveResult::NoSolution => Err("Level has no solution".to_string()),
+        SolveResult::MultipleSolutions(_) => Err("Level has multiple solutions".to_string()),
+    }
+}
+
+/// 读取关卡文件（JSON，或 `.txt` 文本棋盘图）
+fn read_level(path: &Path) -> Result<GameState, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if path.extension().is_some_and(|ext| ext == "txt") {
+        return text.parse().map_err(|e| format!("{}: {}", path.display(), e));
+    }
+    serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// 写入关卡文件
+fn write_level(path: &Path, level: &GameState) -> Result<(), String> {
+    fs::write(path, to_json(level)?).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_file_roundtrip() {
+        let level = Generator::with_seed(7).generate(Difficulty::Easy).unwrap();
+        let path = std::env::temp_dir().join(format!("logic-puzzle-cli-{}.json", std::process::id()));
+
+        write_level(&path, &level).unwrap();
+        let loaded = read_level(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.board, level.board);
+        assert_eq!(loaded.pieces, level.pieces);
+
+        let path = path.with_extension("txt");
+        fs::write(&path, level.board.to_string()).unwrap();
+        let parsed = read_level(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(parsed.pieces.len(), level.pieces.len());
+        assert_eq!(parsed.board.to_string(), level.board.to_string());
+    }
+
+    #[test]
+    fn test_cli_parses_subcommands() {
+        let cli = Cli::try_parse_from(["logic-puzzle-cli", "generate", "--difficulty", "hard", "--seed", "3", "--count", "2"]).unwrap();
+        assert!(matches!(cli.command, Command::Generate { difficulty: Difficulty::Hard, seed: Some(3), count: 2, .. }));
+        assert!(Cli::try_parse_from(["logic-puzzle-cli", "generate", "--difficulty", "extreme"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "logic-puzzle-cli", "generate", "--border-obstacles", "--no-obstacles-in", "2,2,4,4", "--corner", "10",
+            "--vertical", "11",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Generate { constraints, .. } => {
+                let expected = GenerationConstraints::new()
+                    .obstacles_touch_border()
+                    .no_obstacles_in(2, 2, 4, 4)
+                    .piece_in_corner(10)
+                    .piece_orientation(11, Orientation::Vertical);
+                assert_eq!(constraints.constraints().unwrap(), expected);
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["logic-puzzle-cli", "generate", "--shape", "heart"]).unwrap();
+        assert!(matches!(cli.command, Command::Generate { shape: Some(ref s), voids: None, .. } if s == "heart"));
+        assert!(Cli::try_parse_from(["logic-puzzle-cli", "generate", "--shape", "heart", "--voids", "donut"]).is_err());
+        let cli = Cli::try_parse_from(["logic-puzzle-cli", "generate", "--voids", "donut", "--strategy", "obstacles", "--report"]).unwrap();
+        assert!(matches!(cli.command, Command::Generate { strategy: GenerationStrategy::FromObstacles, report: true, voids: Some(Voids::Donut), .. }));
+        assert_eq!(parse_shape("staircase"), Ok(BoardMask::staircase()));
+
+        let cli = Cli::try_parse_from(["logic-puzzle-cli", "render", "a.json", "--charset", "ascii", "--outline"]).unwrap();
+        match cli.command {
+            Command::Render { render, .. } => {
+                let options = render.options();
+                assert_eq!(options.charset, RenderCharset::Ascii);
+                assert!(options.outlines && !options.ansi_color);
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["logic-puzzle-cli", "enumerate", "--pieces", "1,2", "--limit", "5", "--max-solutions", "100"]).unwrap();
+        assert!(matches!(cli.command, Command::Enumerate { ref pieces, limit: Some(5), max_solutions: 100, .. } if pieces == &[1, 2]));
+        assert!(Cli::try_parse_from(["logic-puzzle-cli", "enumerate", "--max-solutions", "1"]).is_err());
+        assert!(matches!(Cli::try_parse_from(["logic-puzzle-cli", "count", "a.json", "--limit", "5"]).unwrap().command, Command::Count { limit: 5, .. }));
+        assert!(Cli::try_parse_from(["logic-puzzle-cli", "count", "a.json", "--limit", "0"]).is_err());
+
+        let cli = Cli::try_parse_from(["logic-puzzle-cli", "model", "a.json", "--format", "lp", "--exclude-solution", "--out", "a.lp"]).unwrap();
+        assert!(matches!(cli.command, Command::Model { format: ModelFormat::Lp, exclude_solution: true, .. }));
+    }
+}
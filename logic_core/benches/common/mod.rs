@@ -0,0 +1,17 @@
+//! 基准测试共用的关卡集
+
+use logic_core::GameState;
+
+/// 读取 `benches/corpus.txt` 中的关卡（名称，关卡）
+pub fn corpus() -> Vec<(String, GameState)> {
+    let text = include_str!("../corpus.txt");
+    let mut levels = Vec::new();
+
+    for section in text.split("\n== ").skip(1) {
+        let (name, diagram) = section.split_once('\n').expect("corpus section without board");
+        let level: GameState = diagram.parse().unwrap_or_else(|e| panic!("{}: {}", name, e));
+        levels.push((name.trim().to_string(), level));
+    }
+
+    levels
+}
@@ -0,0 +1,23 @@
+//! 生成器基准测试：两种生成方式各生成一关（固定种子，结果可复现）
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use logic_core::{Difficulty, Generator};
+
+fn generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate");
+    group.sample_size(20);
+
+    group.bench_function("from_solution", |b| {
+        let generator = Generator::with_seed(1);
+        b.iter(|| generator.generate_from_solution(Difficulty::Medium))
+    });
+    group.bench_function("from_obstacles", |b| {
+        let generator = Generator::with_seed(1);
+        b.iter(|| generator.generate_from_obstacles(Difficulty::Medium))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, generate);
+criterion_main!(benches);
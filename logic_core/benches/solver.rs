@@ -0,0 +1,82 @@
+//! 求解器基准测试：空棋盘、固定关卡集求一个解、唯一性检查、各分支策略对比、置换表穷举计数
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use logic_core::piece::get_standard_pieces;
+use logic_core::{BranchingStrategy, GameState, Solver};
+
+mod common;
+
+fn solve_empty_board(c: &mut Criterion) {
+    let state = GameState::new(get_standard_pieces());
+    let solver = Solver::new(1);
+
+    c.bench_function("solve/empty_board", |b| b.iter(|| solver.solve(black_box(&state))));
+}
+
+fn solve_corpus(c: &mut Criterion) {
+    let corpus = common::corpus();
+
+    let mut group = c.benchmark_group("solve/first_solution");
+    let solver = Solver::new(1);
+    for (name, level) in &corpus {
+        group.bench_with_input(BenchmarkId::from_parameter(name), level, |b, level| {
+            b.iter(|| solver.solve(black_box(level)))
+        });
+    }
+    group.finish();
+
+    // 生成器校验关卡时使用的唯一性检查
+    let mut group = c.benchmark_group("solve/uniqueness");
+    let solver = Solver::new(2);
+    for (name, level) in &corpus {
+        group.bench_with_input(BenchmarkId::from_parameter(name), level, |b, level| {
+            b.iter(|| solver.count_solutions(black_box(level)))
+        });
+    }
+    group.finish();
+}
+
+const STRATEGIES: [(&str, BranchingStrategy); 3] = [
+    ("first_empty", BranchingStrategy::FirstEmpty),
+    ("most_constrained_cell", BranchingStrategy::MostConstrainedCell),
+    ("piece_first", BranchingStrategy::PieceFirst),
+];
+
+/// 各分支策略做唯一性检查：先打印每关的搜索节点数，再测时间
+fn solve_branching(c: &mut Criterion) {
+    let corpus = common::corpus();
+
+    println!("{:<20} {:>14} {:>24} {:>14}", "level", STRATEGIES[0].0, STRATEGIES[1].0, STRATEGIES[2].0);
+    for (name, level) in &corpus {
+        let nodes = STRATEGIES.map(|(_, strategy)| Solver::new(2).with_branching(strategy).search_stats(level).nodes);
+        println!("{:<20} {:>14} {:>24} {:>14}", name, nodes[0], nodes[1], nodes[2]);
+    }
+
+    for (strategy_name, strategy) in STRATEGIES {
+        let mut group = c.benchmark_group(format!("solve/branching/{}", strategy_name));
+        let solver = Solver::new(2).with_branching(strategy);
+        for (name, level) in &corpus {
+            group.bench_with_input(BenchmarkId::from_parameter(name), level, |b, level| {
+                b.iter(|| solver.count_solutions(black_box(level)))
+            });
+        }
+        group.finish();
+    }
+}
+
+/// 空棋盘统计前若干个解：有无置换表对比
+fn count_with_memo(c: &mut Criterion) {
+    let state = GameState::new(get_standard_pieces());
+    let mut group = c.benchmark_group("count/empty_board_100000");
+    group.sample_size(10);
+    for memo in [false, true] {
+        let solver = Solver::new(100_000).with_memo(memo);
+        let stats = solver.search_stats(&state);
+        println!("memo={}: {} nodes, {} memo hits", memo, stats.nodes, stats.memo_hits);
+        group.bench_function(if memo { "memo" } else { "plain" }, |b| b.iter(|| solver.count_solutions(black_box(&state))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, solve_empty_board, solve_corpus, solve_branching, count_with_memo);
+criterion_main!(benches);
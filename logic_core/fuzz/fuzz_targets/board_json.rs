@@ -0,0 +1,17 @@
+//! 棋盘JSON反序列化：任意输入不能panic，成功解析的棋盘可以渲染并解析回来
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use logic_core::{Board, RenderOptions};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(board) = serde_json::from_slice::<Board>(data) else { return };
+
+    let text = board.render(&RenderOptions::ascii());
+    // 方块ID超出字符集范围时渲染为'?'，不要求能解析回来
+    if let Ok(parsed) = text.parse::<Board>() {
+        assert_eq!(parsed.render(&RenderOptions::ascii()), text);
+    }
+    let _ = board.canonical();
+});
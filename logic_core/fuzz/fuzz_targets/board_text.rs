@@ -0,0 +1,14 @@
+//! 文本棋盘图解析：任意输入不能panic，成功解析的文本渲染后再解析结果不变
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use logic_core::{Board, GameState};
+
+fuzz_target!(|text: &str| {
+    if let Ok(board) = text.parse::<Board>() {
+        let reparsed: Board = board.to_string().parse().expect("rendered board should parse");
+        assert_eq!(reparsed.to_string(), board.to_string());
+    }
+    let _ = text.parse::<GameState>();
+});
@@ -0,0 +1,18 @@
+//! 关卡JSON反序列化：任意输入不能panic，解析出的关卡可以创建会话和校验
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use logic_core::{verify_solution, GameState, Session};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(level) = serde_json::from_slice::<GameState>(data) else { return };
+
+    let _ = verify_solution(&level, &level.board);
+    let mut session = Session::new(level);
+    let _ = session.progress();
+    if let Some(piece) = session.pieces().first().map(|p| p.id) {
+        let _ = session.place(piece, 0, 0);
+        let _ = session.remove(piece);
+    }
+});
@@ -0,0 +1,17 @@
+//! 关卡包JSON加载：任意输入不能panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use logic_core::LevelPack;
+
+fuzz_target!(|data: &str| {
+    let Ok(mut pack) = LevelPack::from_json(data) else { return };
+
+    let _ = pack.summaries();
+    let _ = pack.next_unsolved();
+    if !pack.levels.is_empty() {
+        pack.record_completion(0, 1000);
+    }
+    let _ = pack.to_json();
+});
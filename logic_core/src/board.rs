@@ -3,35 +3,12 @@
 use crate::types::Board;
 use crate::BOARD_SIZE;
 
-/// 棋盘辅助函数
+// 棋盘辅助函数
 
 impl Board {
     /// 打印棋盘（用于调试）
     pub fn print(&self) {
-        println!("┌{}┐", "──".repeat(BOARD_SIZE));
-        for row in 0..BOARD_SIZE {
-            print!("│");
-            for col in 0..BOARD_SIZE {
-                let cell = self.get(row, col);
-                let ch = match cell {
-                    -1 => "■",
-                    0 => "·",
-                    n if n >= 1 && n <= 9 => {
-                        print!("{} ", n);
-                        continue;
-                    }
-                    n if n >= 10 && n <= 11 => {
-                        let ch = (b'A' + (n - 10) as u8) as char;
-                        print!("{} ", ch);
-                        continue;
-                    }
-                    _ => "?",
-                };
-                print!("{} ", ch);
-            }
-            println!("│");
-        }
-        println!("└{}┘", "──".repeat(BOARD_SIZE));
+        print!("{}", self);
     }
 
     /// 清空棋盘
@@ -45,11 +22,47 @@ impl Board {
     pub fn cells_mut(&mut self) -> &mut [i8; 64] {
         &mut self.cells
     }
+
+    /// 棋盘的8种对称变换（旋转0/90/180/270度，以及各自的镜像）
+    pub fn symmetries(&self) -> [Board; 8] {
+        let n = BOARD_SIZE - 1;
+
+        std::array::from_fn(|symmetry| {
+            let mut board = Board::new();
+            for row in 0..BOARD_SIZE {
+                for col in 0..BOARD_SIZE {
+                    let (r, c) = match symmetry {
+                        0 => (row, col),
+                        1 => (col, n - row),
+                        2 => (n - row, n - col),
+                        3 => (n - col, row),
+                        4 => (row, n - col),
+                        5 => (n - row, col),
+                        6 => (col, row),
+                        _ => (n - col, n - row),
+                    };
+                    board.set(r, c, self.get(row, col));
+                }
+            }
+            board
+        })
+    }
+
+    /// 对称类的规范形式（8种变换中cells字典序最小的一个）
+    ///
+    /// 两个棋盘互为旋转或镜像时，规范形式相同。
+    pub fn canonical(&self) -> Board {
+        self.symmetries()
+            .into_iter()
+            .min_by(|a, b| a.cells.cmp(&b.cells))
+            .unwrap_or_else(|| self.clone())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use crate::types::Piece;
     use crate::types::Color;
 
@@ -130,6 +143,24 @@ mod tests {
         assert!(board.is_full());
     }
 
+    #[test]
+    fn test_canonical_symmetry() {
+        let mut board = Board::new();
+        board.set(0, 1, -2);
+        board.set(0, 2, -2);
+
+        // 旋转和镜像后的棋盘属于同一对称类
+        for symmetric in board.symmetries() {
+            assert_eq!(symmetric.canonical(), board.canonical());
+            assert_eq!(symmetric.count_empty(), board.count_empty());
+        }
+
+        let mut other = Board::new();
+        other.set(1, 1, -2);
+        other.set(1, 2, -2);
+        assert_ne!(other.canonical(), board.canonical());
+    }
+
     #[test]
     fn test_find_first_empty() {
         let mut board = Board::new();
@@ -146,4 +177,36 @@ mod tests {
         }
         assert_eq!(board.find_first_empty(), None);
     }
+
+    #[test]
+    fn test_can_place_huge_piece() {
+        // 反序列化得到的方块尺寸可能非常大，不能溢出
+        let piece = Piece::new(4, usize::MAX, 1, Color::Blue1);
+        assert!(!Board::new().can_place(&piece, 1, 1));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_place_then_remove_is_identity(
+            board in crate::testing::board(),
+            piece in crate::testing::piece(),
+            row in 0..BOARD_SIZE,
+            col in 0..BOARD_SIZE,
+        ) {
+            prop_assume!(board.can_place(&piece, row, col));
+            let mut after = board.clone();
+            after.place(&piece, row, col);
+            prop_assert_eq!(after.count_empty(), board.count_empty() - piece.area());
+            after.remove(&piece, row, col);
+            prop_assert_eq!(after, board);
+        }
+
+        #[test]
+        fn prop_canonical_is_symmetry_invariant(board in crate::testing::board()) {
+            let canonical = board.canonical();
+            for symmetric in board.symmetries() {
+                prop_assert_eq!(symmetric.canonical(), canonical.clone());
+            }
+        }
+    }
 }
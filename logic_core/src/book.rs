@@ -0,0 +1,241 @@
+//! 谜题书模块 - 批量生成唯一解关卡，排版成可打印的HTML（题目页 + 答案页）
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use crate::export::{
+    board_elements, escape_xml, puzzle_page_elements, svg_document, ExportOptions, A4_HEIGHT_MM, A4_WIDTH_MM,
+};
+use crate::generator::Generator;
+use crate::pack::{difficulty_score, LevelPack};
+use crate::solver::Solver;
+use crate::types::{Board, Difficulty, GameState, SolveResult, Solution};
+use crate::BOARD_SIZE;
+
+/// 答案页每页的列数和行数
+const ANSWER_COLUMNS: usize = 2;
+const ANSWER_ROWS: usize = 3;
+
+/// 谜题书中的一关
+#[derive(Debug, Clone)]
+pub struct BookEntry {
+    /// 关卡名称
+    pub name: String,
+    /// 关卡内容
+    pub level: GameState,
+    /// 唯一解（用于答案页）
+    pub solution: Solution,
+    /// 难度分数
+    pub score: u32,
+}
+
+/// 谜题书
+#[derive(Debug, Clone)]
+pub struct PuzzleBook {
+    /// 书名
+    pub title: String,
+    /// 关卡（按加入顺序排版）
+    pub entries: Vec<BookEntry>,
+}
+
+impl PuzzleBook {
+    /// 创建空的谜题书
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// 加入一关（必须有唯一解）
+    pub fn push(&mut self, name: &str, level: GameState) -> Result<(), String> {
+        let solution = match Solver::new(2).solve(&level) {
+            SolveResult::UniqueSolution(solution) => solution,
+            SolveResult::NoSolution => return Err(format!("{}: level has no solution", name)),
+            SolveResult::MultipleSolutions(_) => return Err(format!("{}: level has multiple solutions", name)),
+        };
+        let score = difficulty_score(&level).unwrap_or(0);
+
+        self.entries.push(BookEntry {
+            name: name.to_string(),
+            level,
+            solution,
+            score,
+        });
+        Ok(())
+    }
+
+    /// 从关卡包创建谜题书（保持关卡包的顺序）
+    pub fn from_pack(pack: &LevelPack) -> Result<Self, String> {
+        let mut book = Self::new(&pack.metadata.name);
+        for level in &pack.levels {
+            book.push(&level.name, level.level.clone())?;
+        }
+        Ok(book)
+    }
+
+    /// 按难度分数从易到难排序
+    pub fn sort_by_difficulty(&mut self) {
+        self.entries.sort_by_key(|entry| entry.score);
+    }
+
+    /// 排版为多页HTML（A4，每页一个SVG），可直接在浏览器中打印成PDF
+    ///
+    /// 第一页是封面，接着每关一页，最后是答案页（每页6个答案）。
+    /// `options.cell_size` 是题目页每格的毫米数。
+    pub fn to_html(&self, options: &ExportOptions) -> String {
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+             @page {{ size: A4; margin: 0; }}\n\
+             body {{ margin: 0; }}\n\
+             .page {{ width: {}mm; height: {}mm; page-break-after: always; break-after: page; }}\n\
+             .page svg {{ display: block; }}\n\
+             </style>\n</head>\n<body>\n",
+            escape_xml(&self.title),
+            A4_WIDTH_MM,
+            A4_HEIGHT_MM
+        );
+
+        html.push_str(&page(&self.cover_elements()));
+        for (i, entry) in self.entries.iter().enumerate() {
+            let title = format!("{}. {}（难度 {}）", i + 1, entry.name, entry.score);
+            html.push_str(&page(&puzzle_page_elements(&entry.level, &title, options)));
+        }
+        for (chunk_index, chunk) in self.entries.chunks(ANSWER_COLUMNS * ANSWER_ROWS).enumerate() {
+            html.push_str(&page(&answer_page_elements(chunk, chunk_index * ANSWER_COLUMNS * ANSWER_ROWS)));
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// 页数（封面 + 题目页 + 答案页）
+    pub fn page_count(&self) -> usize {
+        1 + self.entries.len() + self.entries.len().div_ceil(ANSWER_COLUMNS * ANSWER_ROWS)
+    }
+
+    fn cover_elements(&self) -> String {
+        let center = A4_WIDTH_MM as f64 / 2.0;
+        format!(
+            "<rect width=\"{w}\" height=\"{h}\" fill=\"#ffffff\"/>\n\
+             <text x=\"{c}\" y=\"120\" font-family=\"sans-serif\" font-size=\"16\" text-anchor=\"middle\" fill=\"#2c3e50\">{title}</text>\n\
+             <text x=\"{c}\" y=\"140\" font-family=\"sans-serif\" font-size=\"7\" text-anchor=\"middle\" fill=\"#7f8c8d\">{count} 关 · 答案在书后</text>\n",
+            w = A4_WIDTH_MM,
+            h = A4_HEIGHT_MM,
+            c = center,
+            title = escape_xml(&self.title),
+            count = self.entries.len(),
+        )
+    }
+}
+
+/// 一页HTML
+fn page(elements: &str) -> String {
+    format!("<section class=\"page\">\n{}</section>\n", svg_document(A4_WIDTH_MM, A4_HEIGHT_MM, "mm", elements))
+}
+
+/// 答案页：多个答案棋盘排成网格，标出方块ID
+fn answer_page_elements(entries: &[BookEntry], first_index: usize) -> String {
+    let page_margin = 15.0;
+    let options = ExportOptions {
+        cell_size: 9,
+        margin: 0,
+        grid_lines: false,
+        outlines: true,
+        labels: true,
+    };
+    let board_size = options.cell_size as f64 * BOARD_SIZE as f64;
+    let slot_width = (A4_WIDTH_MM as f64 - page_margin * 2.0) / ANSWER_COLUMNS as f64;
+    let slot_height = (A4_HEIGHT_MM as f64 - page_margin * 2.0 - 10.0) / ANSWER_ROWS as f64;
+
+    let mut out = String::new();
+    let _ = writeln!(out, "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>", A4_WIDTH_MM, A4_HEIGHT_MM);
+    let _ = writeln!(
+        out,
+        "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"8\" text-anchor=\"middle\" fill=\"#2c3e50\">答案</text>",
+        A4_WIDTH_MM as f64 / 2.0,
+        page_margin + 6.0
+    );
+
+    for (i, entry) in entries.iter().enumerate() {
+        let (col, row) = (i % ANSWER_COLUMNS, i / ANSWER_COLUMNS);
+        let x = page_margin + col as f64 * slot_width + (slot_width - board_size) / 2.0;
+        let y = page_margin + 10.0 + row as f64 * slot_height + 8.0;
+        let _ = writeln!(
+            out,
+            "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"5\" fill=\"#2c3e50\">{}. {}</text>",
+            x,
+            y - 2.0,
+            first_index + i + 1,
+            escape_xml(&entry.name)
+        );
+        out.push_str(&board_elements(&entry.solution.board, x, y, &options));
+    }
+    out
+}
+
+impl Generator {
+    /// 生成包含 `count` 个唯一解关卡的谜题书，按难度从易到难排列
+    ///
+    /// 关卡的障碍布局互不对称；生成失败次数过多时返回None。
+    pub fn generate_book(&self, title: &str, count: usize) -> Option<PuzzleBook> {
+        let mut seen: HashSet<Board> = HashSet::new();
+        let mut book = PuzzleBook::new(title);
+        let mut attempts = 0;
+
+        while book.entries.len() < count {
+            attempts += 1;
+            if attempts > count * 10 {
+                return None;
+            }
+            let difficulty = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard][attempts % 3];
+            let Some(level) = self.generate(difficulty) else { continue };
+            if !seen.insert(level.board.canonical()) {
+                continue;
+            }
+            let name = format!("#{}", book.entries.len() + 1);
+            // 生成器只返回唯一解关卡，push不会失败
+            book.push(&name, level).expect("generator returns uniquely solvable levels");
+        }
+
+        book.sort_by_difficulty();
+        for (i, entry) in book.entries.iter_mut().enumerate() {
+            entry.name = format!("第{}关", i + 1);
+        }
+        Some(book)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_book_sorted_with_answers() {
+        let book = Generator::with_seed(11).generate_book("测试", 7).unwrap();
+
+        assert_eq!(book.entries.len(), 7);
+        assert!(book.entries.windows(2).all(|w| w[0].score <= w[1].score));
+        for entry in &book.entries {
+            assert!(entry.solution.board.is_full());
+        }
+
+        let html = book.to_html(&ExportOptions { cell_size: 12, ..ExportOptions::default() });
+        assert_eq!(book.page_count(), 1 + 7 + 2);
+        assert_eq!(html.matches("<section class=\"page\">").count(), book.page_count());
+        assert!(html.contains("7. 第7关"));
+        // 题目页托盘和答案页中每个方块各有一个标签
+        let pieces: usize = book.entries.iter().map(|e| e.level.pieces.len()).sum();
+        assert_eq!(html.matches("dominant-baseline=\"central\">").count(), pieces * 2);
+    }
+
+    #[test]
+    fn test_push_rejects_ambiguous_level() {
+        let mut book = PuzzleBook::new("测试");
+        let level = GameState::new(crate::piece::get_standard_pieces());
+        assert!(book.push("空棋盘", level).is_err());
+        assert!(book.entries.is_empty());
+    }
+}
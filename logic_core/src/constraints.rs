@@ -0,0 +1,333 @@
+//! 生成约束模块 - 让设计者指定关卡必须满足的性质（障碍位置、方块在解中的位置和朝向）
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::piece::get_piece_by_id;
+use crate::types::{Board, Color, Piece};
+use crate::{BOARD_SIZE, VOID_CELL};
+
+/// 方块朝向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Orientation {
+    /// 横放（宽 > 高）
+    Horizontal,
+    /// 竖放（高 > 宽）
+    Vertical,
+}
+
+/// 棋盘上的矩形区域
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub row: usize,
+    pub col: usize,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Region {
+    fn contains(&self, row: usize, col: usize) -> bool {
+        (self.row..self.row + self.height).contains(&row) && (self.col..self.col + self.width).contains(&col)
+    }
+}
+
+/// 约束错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstraintError {
+    /// 方块ID不存在
+    UnknownPiece(u8),
+    /// 正方形方块没有朝向
+    SquarePiece(u8),
+    /// 同一方块同时要求横放和竖放
+    ConflictingOrientation(u8),
+    /// 区域为空或超出棋盘
+    InvalidRegion(Region),
+    /// 障碍方块在约束下没有任何可放置的位置
+    NoObstaclePosition(u8),
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintError::UnknownPiece(id) => write!(f, "Unknown piece {}", id),
+            ConstraintError::SquarePiece(id) => write!(f, "Piece {} is square and has no orientation", id),
+            ConstraintError::ConflictingOrientation(id) => {
+                write!(f, "Piece {} cannot be both horizontal and vertical", id)
+            }
+            ConstraintError::InvalidRegion(r) => write!(
+                f,
+                "Region {}x{} at ({}, {}) is empty or outside the board",
+                r.width, r.height, r.row, r.col
+            ),
+            ConstraintError::NoObstaclePosition(id) => {
+                write!(f, "Obstacle piece {} has no position satisfying the constraints", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+/// 关卡生成约束（构建器）
+///
+/// ```
+/// use logic_core::constraints::{GenerationConstraints, Orientation};
+///
+/// let constraints = GenerationConstraints::new()
+///     .obstacles_touch_border()
+///     .no_obstacles_in(2, 2, 4, 4)
+///     .piece_in_corner(11)
+///     .piece_orientation(11, Orientation::Vertical);
+/// assert!(constraints.validate(&[1, 2, 3]).is_ok());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenerationConstraints {
+    /// 每个障碍方块都必须贴着棋盘边缘
+    pub obstacles_touch_border: bool,
+    /// 这些区域内不能有障碍
+    pub obstacle_free_regions: Vec<Region>,
+    /// 这些方块在解中必须占据一个角
+    pub pieces_in_corner: Vec<u8>,
+    /// 这些方块在解中的朝向
+    pub piece_orientations: Vec<(u8, Orientation)>,
+}
+
+impl GenerationConstraints {
+    /// 创建空约束
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 要求每个障碍方块都贴着棋盘边缘
+    pub fn obstacles_touch_border(mut self) -> Self {
+        self.obstacles_touch_border = true;
+        self
+    }
+
+    /// 禁止在指定区域内放置障碍
+    pub fn no_obstacles_in(mut self, row: usize, col: usize, height: usize, width: usize) -> Self {
+        self.obstacle_free_regions.push(Region { row, col, height, width });
+        self
+    }
+
+    /// 要求方块在解中占据一个角
+    pub fn piece_in_corner(mut self, piece_id: u8) -> Self {
+        self.pieces_in_corner.push(piece_id);
+        self
+    }
+
+    /// 要求方块在解中的朝向
+    pub fn piece_orientation(mut self, piece_id: u8, orientation: Orientation) -> Self {
+        self.piece_orientations.push((piece_id, orientation));
+        self
+    }
+
+    /// 是否没有任何约束
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 检查约束本身是否可满足（`obstacle_ids` 为会被用作障碍的方块）
+    pub fn validate(&self, obstacle_ids: &[u8]) -> Result<(), ConstraintError> {
+        for region in &self.obstacle_free_regions {
+            if region.height == 0
+                || region.width == 0
+                || region.row + region.height > BOARD_SIZE
+                || region.col + region.width > BOARD_SIZE
+            {
+                return Err(ConstraintError::InvalidRegion(*region));
+            }
+        }
+
+        for &id in &self.pieces_in_corner {
+            get_piece_by_id(id).ok_or(ConstraintError::UnknownPiece(id))?;
+        }
+
+        for &(id, orientation) in &self.piece_orientations {
+            let piece = get_piece_by_id(id).ok_or(ConstraintError::UnknownPiece(id))?;
+            if piece.width == piece.height {
+                return Err(ConstraintError::SquarePiece(id));
+            }
+            if self.piece_orientations.iter().any(|&(other, o)| other == id && o != orientation) {
+                return Err(ConstraintError::ConflictingOrientation(id));
+            }
+        }
+
+        for &id in obstacle_ids {
+            let piece = get_piece_by_id(id).ok_or(ConstraintError::UnknownPiece(id))?;
+            if !self.has_obstacle_position(&piece) {
+                return Err(ConstraintError::NoObstaclePosition(id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 障碍方块能否放在 (row, col)（piece为实际朝向）
+    pub fn allows_obstacle(&self, piece: &Piece, row: usize, col: usize) -> bool {
+        let (bottom, right) = (row + piece.height - 1, col + piece.width - 1);
+        if self.obstacles_touch_border && row != 0 && col != 0 && bottom != BOARD_SIZE - 1 && right != BOARD_SIZE - 1 {
+            return false;
+        }
+        if let Some(&(_, orientation)) = self.piece_orientations.iter().find(|&&(id, _)| id == piece.id) {
+            if orientation_of(piece.width, piece.height) != Some(orientation) {
+                return false;
+            }
+        }
+        !self.obstacle_free_regions.iter().any(|region| {
+            (row..=bottom).any(|r| (col..=right).any(|c| region.contains(r, c)))
+        })
+    }
+
+    /// 障碍布局是否满足约束（棋盘上的负数格子为障碍）
+    pub fn accepts_obstacles(&self, board: &Board) -> bool {
+        piece_bounds(board, |cell| cell < 0 && cell != VOID_CELL).into_iter().all(|(id, (top, left, bottom, right))| {
+            let piece = Piece::new(id, right - left + 1, bottom - top + 1, Color::Black1);
+            self.allows_obstacle(&piece, top, left)
+        })
+    }
+
+    /// 完整解是否满足约束（障碍为负数，方块为正数）
+    pub fn accepts_solution(&self, board: &Board) -> bool {
+        let bounds = piece_bounds(board, |cell| cell != 0 && cell != VOID_CELL);
+        let find = |id: u8| bounds.iter().find(|(piece_id, _)| *piece_id == id).map(|(_, b)| *b);
+
+        let corners_ok = self.pieces_in_corner.iter().all(|&id| {
+            find(id).is_some_and(|(top, left, bottom, right)| {
+                (top == 0 || bottom == BOARD_SIZE - 1) && (left == 0 || right == BOARD_SIZE - 1)
+            })
+        });
+        let orientations_ok = self.piece_orientations.iter().all(|&(id, orientation)| {
+            find(id).is_some_and(|(top, left, bottom, right)| {
+                orientation_of(right - left + 1, bottom - top + 1) == Some(orientation)
+            })
+        });
+
+        corners_ok && orientations_ok
+    }
+
+    /// 障碍方块在约束下是否至少有一个位置
+    fn has_obstacle_position(&self, piece: &Piece) -> bool {
+        let mut rotated = piece.clone();
+        rotated.rotate();
+        [piece, &rotated].into_iter().any(|p| {
+            (0..=BOARD_SIZE - p.height)
+                .any(|row| (0..=BOARD_SIZE - p.width).any(|col| self.allows_obstacle(p, row, col)))
+        })
+    }
+}
+
+fn orientation_of(width: usize, height: usize) -> Option<Orientation> {
+    match width.cmp(&height) {
+        std::cmp::Ordering::Greater => Some(Orientation::Horizontal),
+        std::cmp::Ordering::Less => Some(Orientation::Vertical),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// 满足条件的格子按方块ID（取绝对值）分组的外接矩形 (top, left, bottom, right)
+fn piece_bounds(board: &Board, include: impl Fn(i8) -> bool) -> Vec<(u8, (usize, usize, usize, usize))> {
+    let mut bounds: Vec<(u8, (usize, usize, usize, usize))> = Vec::new();
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            let cell = board.get(row, col);
+            if !include(cell) {
+                continue;
+            }
+            let id = cell.unsigned_abs();
+            match bounds.iter_mut().find(|(existing, _)| *existing == id) {
+                Some((_, b)) => {
+                    b.0 = b.0.min(row);
+                    b.1 = b.1.min(col);
+                    b.2 = b.2.max(row);
+                    b.3 = b.3.max(col);
+                }
+                None => bounds.push((id, (row, col, row, col))),
+            }
+        }
+    }
+    bounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Generator;
+    use crate::types::Difficulty;
+
+    #[test]
+    fn test_validate_reports_unsatisfiable_constraints() {
+        let obstacles = [1, 2, 3];
+        assert_eq!(
+            GenerationConstraints::new().piece_in_corner(12).validate(&obstacles),
+            Err(ConstraintError::UnknownPiece(12))
+        );
+        assert_eq!(
+            GenerationConstraints::new().piece_orientation(6, Orientation::Vertical).validate(&obstacles),
+            Err(ConstraintError::SquarePiece(6))
+        );
+        assert_eq!(
+            GenerationConstraints::new()
+                .piece_orientation(11, Orientation::Vertical)
+                .piece_orientation(11, Orientation::Horizontal)
+                .validate(&obstacles),
+            Err(ConstraintError::ConflictingOrientation(11))
+        );
+        assert!(matches!(
+            GenerationConstraints::new().no_obstacles_in(6, 6, 4, 4).validate(&obstacles),
+            Err(ConstraintError::InvalidRegion(_))
+        ));
+        // 障碍必须贴边，但只留出左上角一格可以放
+        let constraints = GenerationConstraints::new()
+            .obstacles_touch_border()
+            .no_obstacles_in(0, 1, 1, 7)
+            .no_obstacles_in(1, 0, 7, 8);
+        assert_eq!(constraints.validate(&obstacles), Err(ConstraintError::NoObstaclePosition(2)));
+    }
+
+    #[test]
+    fn test_generate_with_constraints() {
+        let constraints = GenerationConstraints::new()
+            .obstacles_touch_border()
+            .no_obstacles_in(2, 2, 4, 4)
+            .piece_orientation(11, Orientation::Vertical)
+            .piece_in_corner(10);
+        let generator = Generator::with_seed(2).with_constraints(constraints.clone()).unwrap();
+
+        let level = generator.generate(Difficulty::Medium).expect("Constraints should be satisfiable");
+        assert!(constraints.accepts_obstacles(&level.board));
+
+        let solution = match crate::solver::Solver::new(2).solve(&level) {
+            crate::types::SolveResult::UniqueSolution(s) => s,
+            other => panic!("Expected unique solution, got {:?}", other),
+        };
+        assert!(constraints.accepts_solution(&solution.board));
+        for row in 2..6 {
+            for col in 2..6 {
+                assert!(level.board.get(row, col) >= 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_constraints_checked_against_generated_difficulty() {
+        // 1×5只能横放，横放必然经过第3列：简单会用到的1×5放不下，困难只用1×1到1×3
+        let constraints = GenerationConstraints::new()
+            .piece_orientation(5, Orientation::Horizontal)
+            .no_obstacles_in(0, 3, 8, 1);
+        assert_eq!(
+            constraints.validate(&Difficulty::Easy.get_obstacle_piece_ids()),
+            Err(ConstraintError::NoObstaclePosition(5))
+        );
+        let generator = Generator::with_seed(1).with_constraints(constraints.clone()).unwrap();
+
+        let level = generator.generate(Difficulty::Hard).expect("Hard obstacles have legal positions");
+        assert!(constraints.accepts_obstacles(&level.board));
+        assert_eq!(crate::solver::Solver::new(2).count_solutions(&level), 1);
+
+        let outcome = generator.generate_with_report(Difficulty::Easy, crate::GenerationStrategy::Auto);
+        assert!(outcome.level.is_none());
+        assert_eq!(outcome.report.attempts, 0);
+    }
+}
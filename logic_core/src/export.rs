@@ -0,0 +1,555 @@
+//! 导出模块 - 把关卡和答案导出为SVG（可选PNG），用于打印和分享
+
+use std::fmt::Write as _;
+
+use crate::piece::get_piece_by_id;
+use crate::types::{Board, GameState, Piece, Solution};
+use crate::{BOARD_SIZE, VOID_CELL};
+
+/// 空格的填充色
+const EMPTY_FILL: &str = "#ffffff";
+/// 网格线颜色
+const GRID_COLOR: &str = "#bdc3c7";
+/// 方块边框颜色
+const OUTLINE_COLOR: &str = "#2c3e50";
+/// 标签文字颜色
+const LABEL_COLOR: &str = "#ffffff";
+
+/// A4纸尺寸（毫米）
+pub const A4_WIDTH_MM: u32 = 210;
+pub const A4_HEIGHT_MM: u32 = 297;
+
+/// 导出选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// 每格边长（SVG用户单位，PNG为像素）
+    pub cell_size: u32,
+    /// 棋盘四周留白
+    pub margin: u32,
+    /// 是否画网格线
+    pub grid_lines: bool,
+    /// 是否在不同方块之间画粗边框
+    pub outlines: bool,
+    /// 是否在方块中央标出方块ID（PNG不支持文字，忽略此项）
+    pub labels: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 40,
+            margin: 8,
+            grid_lines: true,
+            outlines: true,
+            labels: false,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// 棋盘（含留白）的总边长
+    pub fn board_extent(&self) -> u32 {
+        self.cell_size * BOARD_SIZE as u32 + self.margin * 2
+    }
+
+    fn grid_width(&self) -> f64 {
+        (self.cell_size as f64 / 40.0).max(0.5)
+    }
+
+    fn outline_width(&self) -> f64 {
+        (self.cell_size as f64 / 12.0).max(1.0)
+    }
+}
+
+/// 格子的填充色（障碍和方块都取方块颜色，空洞不画）
+fn cell_fill(cell: i8) -> Option<&'static str> {
+    if cell == VOID_CELL {
+        return None;
+    }
+    if cell == 0 {
+        return Some(EMPTY_FILL);
+    }
+    Some(get_piece_by_id(cell.unsigned_abs()).map(|p| p.color.to_css()).unwrap_or(EMPTY_FILL))
+}
+
+/// 只保留障碍的棋盘（题面）
+fn puzzle_board(level: &GameState) -> Board {
+    let mut board = level.board.clone();
+    for cell in board.cells_mut().iter_mut() {
+        if *cell > 0 {
+            *cell = 0;
+        }
+    }
+    board
+}
+
+/// 包装成完整的SVG文档
+pub(crate) fn svg_document(width: u32, height: u32, unit: &str, body: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}{unit}\" height=\"{h}{unit}\" viewBox=\"0 0 {w} {h}\">\n{body}</svg>\n",
+        w = width,
+        h = height,
+        unit = unit,
+        body = body,
+    )
+}
+
+/// 棋盘的SVG元素，左上角位于 (x, y)
+pub(crate) fn board_elements(board: &Board, x: f64, y: f64, options: &ExportOptions) -> String {
+    let cell = options.cell_size as f64;
+    let size = cell * BOARD_SIZE as f64;
+    let mut out = String::new();
+
+    // 格子
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            let Some(fill) = cell_fill(board.get(row, col)) else {
+                continue;
+            };
+            let _ = writeln!(
+                out,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                x + col as f64 * cell,
+                y + row as f64 * cell,
+                cell,
+                cell,
+                fill,
+            );
+        }
+    }
+
+    // 网格线
+    if options.grid_lines {
+        let _ = write!(out, "<g stroke=\"{}\" stroke-width=\"{}\">", GRID_COLOR, options.grid_width());
+        if board.count_void() == 0 {
+            for i in 1..BOARD_SIZE {
+                let offset = i as f64 * cell;
+                let _ = write!(out, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>", x + offset, y, x + offset, y + size);
+                let _ = write!(out, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>", x, y + offset, x + size, y + offset);
+            }
+        } else {
+            for (x1, y1, x2, y2) in grid_segments(board) {
+                let _ = write!(
+                    out,
+                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>",
+                    x + x1 as f64 * cell,
+                    y + y1 as f64 * cell,
+                    x + x2 as f64 * cell,
+                    y + y2 as f64 * cell,
+                );
+            }
+        }
+        out.push_str("</g>\n");
+    }
+
+    // 方块边框：相邻格子属于不同方块（或都是空格）时画线
+    if options.outlines {
+        let _ = write!(
+            out,
+            "<g stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"square\">",
+            OUTLINE_COLOR,
+            options.outline_width()
+        );
+        for (x1, y1, x2, y2) in outline_segments(board) {
+            let _ = write!(
+                out,
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>",
+                x + x1 as f64 * cell,
+                y + y1 as f64 * cell,
+                x + x2 as f64 * cell,
+                y + y2 as f64 * cell,
+            );
+        }
+        out.push_str("</g>\n");
+        if board.count_void() == 0 {
+            let _ = writeln!(
+                out,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                x,
+                y,
+                size,
+                size,
+                OUTLINE_COLOR,
+                options.outline_width()
+            );
+        }
+    }
+
+    // 方块ID标签（方块都是矩形，标在外接矩形中央）
+    if options.labels {
+        for (id, (min_row, min_col, max_row, max_col)) in piece_bounds(board) {
+            let cx = x + (min_col + max_col + 1) as f64 * cell / 2.0;
+            let cy = y + (min_row + max_row + 1) as f64 * cell / 2.0;
+            let _ = writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" fill=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                cx,
+                cy,
+                cell * 0.45,
+                LABEL_COLOR,
+                id
+            );
+        }
+    }
+
+    out
+}
+
+/// 方块边框线段（以格子为单位的端点坐标）
+///
+/// 有空洞时外框不再是整个8x8矩形，棋盘边缘上非空洞格子的边也作为线段返回。
+fn outline_segments(board: &Board) -> Vec<(usize, usize, usize, usize)> {
+    let split = |a: i8, b: i8| a != b || a == 0;
+    let mut segments = Vec::new();
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            let here = board.get(row, col);
+            if col + 1 < BOARD_SIZE && split(here, board.get(row, col + 1)) {
+                segments.push((col + 1, row, col + 1, row + 1));
+            }
+            if row + 1 < BOARD_SIZE && split(here, board.get(row + 1, col)) {
+                segments.push((col, row + 1, col + 1, row + 1));
+            }
+        }
+    }
+    if board.count_void() > 0 {
+        let last = BOARD_SIZE - 1;
+        for i in 0..BOARD_SIZE {
+            for (row, col, segment) in [
+                (0, i, (i, 0, i + 1, 0)),
+                (last, i, (i, BOARD_SIZE, i + 1, BOARD_SIZE)),
+                (i, 0, (0, i, 0, i + 1)),
+                (i, last, (BOARD_SIZE, i, BOARD_SIZE, i + 1)),
+            ] {
+                if board.get(row, col) != VOID_CELL {
+                    segments.push(segment);
+                }
+            }
+        }
+    }
+    segments
+}
+
+/// 相邻两个非空洞格子之间的网格线段（以格子为单位的端点坐标）
+fn grid_segments(board: &Board) -> Vec<(usize, usize, usize, usize)> {
+    let inside = |row: usize, col: usize| board.get(row, col) != VOID_CELL;
+    let mut segments = Vec::new();
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            if !inside(row, col) {
+                continue;
+            }
+            if col + 1 < BOARD_SIZE && inside(row, col + 1) {
+                segments.push((col + 1, row, col + 1, row + 1));
+            }
+            if row + 1 < BOARD_SIZE && inside(row + 1, col) {
+                segments.push((col, row + 1, col + 1, row + 1));
+            }
+        }
+    }
+    segments
+}
+
+/// 每个已放置方块的外接矩形 (min_row, min_col, max_row, max_col)，按ID排序
+fn piece_bounds(board: &Board) -> Vec<(u8, (usize, usize, usize, usize))> {
+    let mut bounds: Vec<(u8, (usize, usize, usize, usize))> = Vec::new();
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            let cell = board.get(row, col);
+            if cell <= 0 {
+                continue;
+            }
+            match bounds.iter_mut().find(|(id, _)| *id == cell as u8) {
+                Some((_, b)) => {
+                    b.0 = b.0.min(row);
+                    b.1 = b.1.min(col);
+                    b.2 = b.2.max(row);
+                    b.3 = b.3.max(col);
+                }
+                None => bounds.push((cell as u8, (row, col, row, col))),
+            }
+        }
+    }
+    bounds.sort_by_key(|(id, _)| *id);
+    bounds
+}
+
+/// 方块托盘的SVG元素（按比例绘制，横放，超出宽度自动换行），返回元素和占用高度
+pub(crate) fn tray_elements(pieces: &[Piece], x: f64, y: f64, max_width: f64, options: &ExportOptions) -> (String, f64) {
+    let cell = options.cell_size as f64;
+    let gap = cell / 2.0;
+    let mut out = String::new();
+    let (mut cursor_x, mut cursor_y, mut shelf_height) = (0.0_f64, 0.0_f64, 0.0_f64);
+
+    for piece in pieces {
+        // 托盘里统一横放，节省纵向空间
+        let (w, h) = (
+            piece.original_width.max(piece.original_height),
+            piece.original_width.min(piece.original_height),
+        );
+        let (pw, ph) = (w as f64 * cell, h as f64 * cell);
+        if cursor_x > 0.0 && cursor_x + pw > max_width {
+            cursor_x = 0.0;
+            cursor_y += shelf_height + gap;
+            shelf_height = 0.0;
+        }
+
+        let (px, py) = (x + cursor_x, y + cursor_y);
+        let _ = writeln!(
+            out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+            px,
+            py,
+            pw,
+            ph,
+            piece.color.to_css(),
+            OUTLINE_COLOR,
+            options.grid_width()
+        );
+        if options.grid_lines {
+            let _ = write!(out, "<g stroke=\"{}\" stroke-width=\"{}\">", LABEL_COLOR, options.grid_width());
+            for i in 1..w {
+                let lx = px + i as f64 * cell;
+                let _ = write!(out, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>", lx, py, lx, py + ph);
+            }
+            for i in 1..h {
+                let ly = py + i as f64 * cell;
+                let _ = write!(out, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>", px, ly, px + pw, ly);
+            }
+            out.push_str("</g>\n");
+        }
+        if options.labels {
+            let _ = writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" fill=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}×{}</text>",
+                px + pw / 2.0,
+                py + ph / 2.0,
+                cell * 0.4,
+                LABEL_COLOR,
+                piece.original_width,
+                piece.original_height
+            );
+        }
+
+        cursor_x += pw + gap;
+        shelf_height = shelf_height.max(ph);
+    }
+
+    (out, cursor_y + shelf_height)
+}
+
+/// 转义SVG文本中的特殊字符
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// 把棋盘导出为SVG
+pub fn board_to_svg(board: &Board, options: &ExportOptions) -> String {
+    let extent = options.board_extent();
+    let margin = options.margin as f64;
+    svg_document(extent, extent, "", &board_elements(board, margin, margin, options))
+}
+
+/// 把关卡题面（只有障碍的空棋盘）导出为SVG
+pub fn puzzle_to_svg(level: &GameState, options: &ExportOptions) -> String {
+    board_to_svg(&puzzle_board(level), options)
+}
+
+/// 把答案导出为SVG
+pub fn solution_to_svg(solution: &Solution, options: &ExportOptions) -> String {
+    board_to_svg(&solution.board, options)
+}
+
+/// 生成可打印的A4题目页：标题、题面棋盘和按比例绘制的方块托盘
+///
+/// 单位为毫米，`options.cell_size` 是每格的毫米数（建议10~14）。
+pub fn puzzle_sheet_svg(level: &GameState, title: &str, options: &ExportOptions) -> String {
+    svg_document(A4_WIDTH_MM, A4_HEIGHT_MM, "mm", &puzzle_page_elements(level, title, options))
+}
+
+/// A4题目页的内容
+pub(crate) fn puzzle_page_elements(level: &GameState, title: &str, options: &ExportOptions) -> String {
+    let page_margin = 15.0;
+    let content_width = A4_WIDTH_MM as f64 - page_margin * 2.0;
+    let board_size = options.cell_size as f64 * BOARD_SIZE as f64;
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>",
+        A4_WIDTH_MM, A4_HEIGHT_MM
+    );
+    let _ = writeln!(
+        out,
+        "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"8\" text-anchor=\"middle\" fill=\"{}\">{}</text>",
+        A4_WIDTH_MM as f64 / 2.0,
+        page_margin + 6.0,
+        OUTLINE_COLOR,
+        escape_xml(title)
+    );
+
+    let board_y = page_margin + 15.0;
+    out.push_str(&board_elements(
+        &puzzle_board(level),
+        (A4_WIDTH_MM as f64 - board_size) / 2.0,
+        board_y,
+        options,
+    ));
+
+    let tray_options = ExportOptions { labels: true, ..*options };
+    let (tray, _) = tray_elements(&level.pieces, page_margin, board_y + board_size + 15.0, content_width, &tray_options);
+    out.push_str(&tray);
+    out
+}
+
+/// 把棋盘光栅化为PNG（纯Rust实现，不支持文字标签）
+#[cfg(feature = "png")]
+pub fn board_to_png(board: &Board, options: &ExportOptions) -> Result<Vec<u8>, String> {
+    use crate::types::parse_hex_rgb;
+
+    let extent = options.board_extent() as usize;
+    let cell = options.cell_size as usize;
+    let margin = options.margin as usize;
+    let mut pixels = vec![255u8; extent * extent * 3];
+
+    let mut fill = |x0: usize, y0: usize, w: usize, h: usize, (r, g, b): (u8, u8, u8)| {
+        for y in y0..(y0 + h).min(extent) {
+            for x in x0..(x0 + w).min(extent) {
+                let idx = (y * extent + x) * 3;
+                pixels[idx..idx + 3].copy_from_slice(&[r, g, b]);
+            }
+        }
+    };
+
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            if let Some(hex) = cell_fill(board.get(row, col)) {
+                fill(margin + col * cell, margin + row * cell, cell, cell, parse_hex_rgb(hex));
+            }
+        }
+    }
+
+    let size = cell * BOARD_SIZE;
+    if options.grid_lines {
+        let width = options.grid_width().round().max(1.0) as usize;
+        let rgb = parse_hex_rgb(GRID_COLOR);
+        if board.count_void() == 0 {
+            for i in 1..BOARD_SIZE {
+                let offset = (margin + i * cell).saturating_sub(width / 2);
+                fill(offset, margin, width, size, rgb);
+                fill(margin, offset, size, width, rgb);
+            }
+        } else {
+            for (x1, y1, x2, y2) in grid_segments(board) {
+                let (x, y) = ((margin + x1 * cell).saturating_sub(width / 2), (margin + y1 * cell).saturating_sub(width / 2));
+                fill(x, y, (x2 - x1) * cell + width, (y2 - y1) * cell + width, rgb);
+            }
+        }
+    }
+
+    if options.outlines {
+        let width = options.outline_width().round() as usize;
+        let half = width / 2;
+        let rgb = parse_hex_rgb(OUTLINE_COLOR);
+        for (x1, y1, x2, y2) in outline_segments(board) {
+            let (x, y) = ((margin + x1 * cell).saturating_sub(half), (margin + y1 * cell).saturating_sub(half));
+            fill(x, y, (x2 - x1) * cell + width, (y2 - y1) * cell + width, rgb);
+        }
+        if board.count_void() == 0 {
+            let (start, end) = (margin.saturating_sub(half), (margin + size).saturating_sub(half));
+            fill(start, start, size + width, width, rgb);
+            fill(start, end, size + width, width, rgb);
+            fill(start, start, width, size + width, rgb);
+            fill(end, start, width, size + width, rgb);
+        }
+    }
+
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, extent as u32, extent as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(&pixels).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Generator;
+    use crate::shape::BoardMask;
+    use crate::solver::Solver;
+    use crate::types::{Difficulty, SolveResult};
+
+    fn level_and_solution() -> (GameState, Solution) {
+        let level = Generator::with_seed(5).generate(Difficulty::Medium).unwrap();
+        let solution = match Solver::new(2).solve(&level) {
+            SolveResult::UniqueSolution(s) => s,
+            other => panic!("Expected unique solution, got {:?}", other),
+        };
+        (level, solution)
+    }
+
+    #[test]
+    fn test_puzzle_svg_shows_only_obstacles() {
+        let (level, _) = level_and_solution();
+        let svg = puzzle_to_svg(&level, &ExportOptions::default());
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"336\" height=\"336\""));
+        assert_eq!(svg.matches("<rect ").count(), 64 + 1);
+        assert_eq!(svg.matches(&format!("fill=\"{}\"", EMPTY_FILL)).count(), 64 - 6);
+        assert!(svg.contains("#1a252f"));
+        assert!(!svg.contains("<text"));
+    }
+
+    #[test]
+    fn test_solution_svg_labels_every_piece() {
+        let (level, solution) = level_and_solution();
+        let options = ExportOptions { labels: true, grid_lines: false, ..ExportOptions::default() };
+        let svg = solution_to_svg(&solution, &options);
+
+        assert_eq!(svg.matches("<text").count(), level.pieces.len());
+        assert!(!svg.contains(&format!("fill=\"{}\"/>", EMPTY_FILL)));
+        assert!(!svg.contains(GRID_COLOR));
+        for piece in &level.pieces {
+            assert!(svg.contains(piece.color.to_css()));
+        }
+    }
+
+    #[test]
+    fn test_puzzle_sheet_fits_a4() {
+        let (level, _) = level_and_solution();
+        let options = ExportOptions { cell_size: 12, ..ExportOptions::default() };
+        let svg = puzzle_sheet_svg(&level, "第1关 <Easy>", &options);
+
+        assert!(svg.contains("width=\"210mm\" height=\"297mm\""));
+        assert!(svg.contains("第1关 &lt;Easy&gt;"));
+
+        let (_, height) = tray_elements(&level.pieces, 0.0, 0.0, 180.0, &options);
+        assert!(15.0 + 15.0 + 96.0 + 15.0 + height < A4_HEIGHT_MM as f64 - 15.0);
+    }
+
+    #[test]
+    fn test_shaped_board_svg_follows_mask() {
+        let board = Board::from_mask(&BoardMask::staircase());
+        let svg = board_to_svg(&board, &ExportOptions::default());
+
+        // 只画形状内的格子，没有8x8外框
+        assert_eq!(svg.matches("<rect ").count(), 40);
+        assert_eq!(grid_segments(&board).len(), 2 * 40 - 8 - 8);
+        // 外边缘：左8 + 底8 + 阶梯的竖边8 + 横边8
+        assert_eq!(outline_segments(&board).len(), grid_segments(&board).len() + 32);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_board_to_png() {
+        let (_, solution) = level_and_solution();
+        let bytes = board_to_png(&solution.board, &ExportOptions::default()).unwrap();
+
+        assert_eq!(&bytes[1..4], b"PNG");
+        // IHDR中的宽度
+        assert_eq!(u32::from_be_bytes(bytes[16..20].try_into().unwrap()), 336);
+    }
+}
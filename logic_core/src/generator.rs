@@ -1,106 +1,528 @@
 //! 关卡生成器模块
 
-use crate::types::{Board, Difficulty, GameState, Piece, Solution, SolveResult};
+use crate::constraints::{ConstraintError, GenerationConstraints};
+use crate::types::{Board, Difficulty, GameState, ObstacleProfile, ParseNameError, Piece, Placement, Solution, SolveResult};
 use crate::piece::get_standard_pieces;
+use crate::session::now_ms;
 use crate::solver::Solver;
-use crate::OBSTACLE_COUNT;
+use crate::{BOARD_SIZE, OBSTACLE_COUNT, VOID_CELL};
 use rand::prelude::*;
+use rand::rngs::StdRng;
 use rand::seq::{IndexedRandom, SliceRandom};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// 添加提示时最多枚举多少个解
+const MAX_CLUE_SOLUTIONS: usize = 10_000;
+
+/// 添加提示时最多访问多少个搜索节点
+const MAX_CLUE_NODES: usize = 2_000_000;
+
+/// 障碍位置列表 (row, col, piece_id)
+type ObstaclePositions = Vec<(usize, usize, u8)>;
+
+/// 生成方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GenerationStrategy {
+    /// 先从完整解反推，失败后改为先放障碍
+    #[default]
+    Auto,
+    /// 从完整解反推
+    FromSolution,
+    /// 先放障碍再求解
+    FromObstacles,
+}
+
+impl FromStr for GenerationStrategy {
+    type Err = ParseNameError;
+
+    /// 解析 `auto` / `solution` / `obstacles`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(GenerationStrategy::Auto),
+            "solution" => Ok(GenerationStrategy::FromSolution),
+            "obstacles" => Ok(GenerationStrategy::FromObstacles),
+            _ => Err(ParseNameError { kind: "generation strategy", name: s.to_string() }),
+        }
+    }
+}
+
+/// 被拒绝的候选关卡数（按原因）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RejectionCounts {
+    /// 有多个解
+    pub not_unique: usize,
+    /// 无解
+    pub no_solution: usize,
+    /// 障碍无法放置或选不出足够的障碍方块
+    pub placement_failed: usize,
+    /// 不满足生成约束
+    pub constraints_failed: usize,
+}
+
+impl RejectionCounts {
+    /// 总拒绝次数
+    pub fn total(&self) -> usize {
+        self.not_unique + self.no_solution + self.placement_failed + self.constraints_failed
+    }
+}
+
+/// 一次生成的统计信息
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenerationReport {
+    /// 调用方选择的生成方式
+    pub requested: GenerationStrategy,
+    /// 实际生成出关卡的方式（失败时为None）
+    pub strategy: Option<GenerationStrategy>,
+    /// 尝试的候选关卡数
+    pub attempts: usize,
+    /// 拒绝原因统计
+    pub rejections: RejectionCounts,
+    /// 耗时（毫秒）
+    pub elapsed_ms: u64,
+}
+
+/// 生成结果：关卡（失败时为None）和统计信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationOutcome {
+    pub level: Option<GameState>,
+    pub report: GenerationReport,
+}
+
+/// 可分步执行的生成任务（见 [`Generator::start_job`]），每次推进只做有限的工作，适合在浏览器主线程中分片运行
+#[derive(Debug, Clone)]
+pub struct GenerationJob {
+    profile: ObstacleProfile,
+    phase: JobPhase,
+    level: Option<GameState>,
+    report: GenerationReport,
+    started_at: u64,
+}
+
+/// 生成任务的当前阶段
+#[derive(Debug, Clone)]
+enum JobPhase {
+    /// 从完整解反推，还剩多少次尝试
+    FromSolution { remaining: usize },
+    /// 即将开始先放障碍的方式（下一步才消耗随机数，保证与一次性生成的顺序相同）
+    Pending,
+    /// 随机摆放障碍，还剩多少次尝试
+    FromObstacles { remaining: usize },
+    /// 逐个检查枚举出的障碍布局（布局，对称变换）
+    Layouts { layouts: std::vec::IntoIter<(Board, usize)> },
+    /// 已结束
+    Done,
+}
+
+impl GenerationJob {
+    /// 任务是否已结束（成功或用完所有尝试）
+    pub fn is_finished(&self) -> bool {
+        matches!(self.phase, JobPhase::Done)
+    }
+
+    /// 目前的统计信息（可用于显示进度）
+    pub fn report(&self) -> &GenerationReport {
+        &self.report
+    }
+
+    /// 生成结果（任务未结束时level为None）
+    pub fn into_outcome(self) -> GenerationOutcome {
+        GenerationOutcome { level: self.level, report: self.report }
+    }
+}
+
+/// 添加提示时的查找表：每个候选放置被哪些解包含
+struct ClueTable<'a> {
+    /// 多解关卡
+    level: &'a GameState,
+    /// 所有解中出现过的放置（不含已有提示）
+    placements: Vec<Placement>,
+    /// 每个解包含的放置（`placements` 的下标）
+    solutions: Vec<Vec<usize>>,
+    /// 每个放置：包含它的解的位集
+    holders: Vec<Vec<u64>>,
+    /// 是否枚举到了全部解
+    complete: bool,
+    /// 剩余的搜索节点数
+    budget: Cell<usize>,
+}
+
+impl<'a> ClueTable<'a> {
+    fn new(level: &'a GameState, solutions: &[Solution]) -> Self {
+        let words = solutions.len().div_ceil(64);
+        let mut ids = HashMap::new();
+        let mut table = Self {
+            level,
+            placements: Vec::new(),
+            solutions: Vec::with_capacity(solutions.len()),
+            holders: Vec::new(),
+            complete: solutions.len() < MAX_CLUE_SOLUTIONS,
+            budget: Cell::new(MAX_CLUE_NODES),
+        };
+
+        for (index, solution) in solutions.iter().enumerate() {
+            // 只有尚未使用的方块可以作为提示
+            let own = solution
+                .placements
+                .iter()
+                .filter(|p| !level.is_clue(p.piece_id))
+                .map(|p| {
+                    let id = *ids.entry((p.piece_id, p.row, p.col, p.rotated)).or_insert_with(|| {
+                        table.placements.push(p.clone());
+                        table.holders.push(vec![0; words]);
+                        table.placements.len() - 1
+                    });
+                    table.holders[id][index / 64] |= 1 << (index % 64);
+                    id
+                })
+                .collect();
+            table.solutions.push(own);
+        }
+        table
+    }
+}
 
 /// 关卡生成器
 pub struct Generator {
-    solver: Solver,
-    max_retries: usize,
+    pub(crate) solver: Solver,
+    pub(crate) max_retries: usize,
+    pub(crate) rng: RefCell<StdRng>,
+    pub(crate) constraints: GenerationConstraints,
 }
 
 impl Generator {
     /// 创建新的生成器
     pub fn new() -> Self {
+        Self::with_rng(StdRng::from_rng(&mut rand::rng()))
+    }
+
+    /// 创建使用固定种子的生成器（相同种子生成相同关卡序列）
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> Self {
         Self {
             solver: Solver::new(2), // 验证唯一解需要找2个
             max_retries: 100,
+            rng: RefCell::new(rng),
+            constraints: GenerationConstraints::default(),
         }
     }
 
+    /// 设置生成约束，约束本身不可满足时返回错误
+    ///
+    /// 障碍方块有没有合法位置取决于难度，在开始生成时按实际的障碍配置检查（见 [`Generator::start_job`]）。
+    pub fn with_constraints(mut self, constraints: GenerationConstraints) -> Result<Self, ConstraintError> {
+        constraints.validate(&[])?;
+        self.constraints = constraints;
+        Ok(self)
+    }
+
+    /// 当前的生成约束
+    pub fn constraints(&self) -> &GenerationConstraints {
+        &self.constraints
+    }
+
     /// 生成关卡（自动选择方式）
     pub fn generate(&self, difficulty: Difficulty) -> Option<GameState> {
-        // 优先使用从完整解反推的方式，因为质量更高
-        self.generate_from_solution(difficulty)
-            .or_else(|| self.generate_from_obstacles(difficulty))
+        self.generate_with_report(difficulty, GenerationStrategy::Auto).level
+    }
+
+    /// 用指定方式生成关卡，同时返回尝试次数、拒绝原因和耗时
+    pub fn generate_with_report(&self, difficulty: Difficulty, strategy: GenerationStrategy) -> GenerationOutcome {
+        self.generate_with_profile(&difficulty.obstacle_profile(), strategy)
+    }
+
+    /// 用自定义障碍配置生成关卡
+    pub fn generate_with_profile(&self, profile: &ObstacleProfile, strategy: GenerationStrategy) -> GenerationOutcome {
+        let mut job = self.start_job(profile, strategy);
+        self.advance(&mut job, usize::MAX);
+        job.into_outcome()
+    }
+
+    /// 开始一个可分步执行的生成任务，用 [`Generator::advance`] 推进
+    ///
+    /// 与 `generate_with_profile` 的尝试顺序完全相同，同一种子得到同一关卡。
+    /// 生成约束使配置中某个候选障碍方块无处可放时，任务直接结束，不做任何尝试。
+    pub fn start_job(&self, profile: &ObstacleProfile, strategy: GenerationStrategy) -> GenerationJob {
+        let phase = match strategy {
+            _ if self.constraints.validate(&profile.candidates).is_err() => JobPhase::Done,
+            // 障碍很少时反推几乎得不到唯一解，直接枚举障碍布局
+            GenerationStrategy::Auto if profile.count < OBSTACLE_COUNT => JobPhase::Pending,
+            // 优先使用从完整解反推的方式，因为质量更高
+            GenerationStrategy::Auto | GenerationStrategy::FromSolution => JobPhase::FromSolution { remaining: self.max_retries },
+            GenerationStrategy::FromObstacles => JobPhase::Pending,
+        };
+
+        GenerationJob {
+            profile: profile.clone(),
+            phase,
+            level: None,
+            report: GenerationReport {
+                requested: strategy,
+                ..GenerationReport::default()
+            },
+            started_at: now_ms(),
+        }
+    }
+
+    /// 推进生成任务，最多尝试 `max_attempts` 个候选关卡，任务结束时返回true
+    pub fn advance(&self, job: &mut GenerationJob, max_attempts: usize) -> bool {
+        let mut budget = max_attempts;
+        while budget > 0 {
+            let level = match &mut job.phase {
+                JobPhase::Done => break,
+                JobPhase::FromSolution { remaining: 0 } => {
+                    job.phase = if job.report.requested == GenerationStrategy::Auto { JobPhase::Pending } else { JobPhase::Done };
+                    continue;
+                }
+                JobPhase::Pending => {
+                    job.phase = self.obstacles_phase(&job.profile);
+                    continue;
+                }
+                JobPhase::FromObstacles { remaining: 0 } => {
+                    job.phase = JobPhase::Done;
+                    continue;
+                }
+                JobPhase::FromSolution { remaining } => {
+                    *remaining -= 1;
+                    self.attempt_from_solution(&job.profile, &mut job.report)
+                }
+                JobPhase::FromObstacles { remaining } => {
+                    *remaining -= 1;
+                    self.attempt_from_obstacles(&job.profile, &mut job.report)
+                }
+                JobPhase::Layouts { layouts } => match layouts.next() {
+                    Some((layout, symmetry)) => self.attempt_layout(layout.symmetries()[symmetry].clone(), &mut job.report),
+                    None => {
+                        job.phase = JobPhase::Done;
+                        continue;
+                    }
+                },
+            };
+
+            budget -= 1;
+            if level.is_some() {
+                job.level = level;
+                job.phase = JobPhase::Done;
+            }
+        }
+
+        let finished = matches!(job.phase, JobPhase::Done);
+        if finished {
+            job.report.elapsed_ms = now_ms().saturating_sub(job.started_at);
+        }
+        finished
     }
 
     /// 方式一：从完整解反推生成关卡
     pub fn generate_from_solution(&self, difficulty: Difficulty) -> Option<GameState> {
-        let mut _rng = rand::rng();
-        for _ in 0..self.max_retries {
-            // 1. 生成一个完整解
-            let solution = self.generate_complete_solution()?;
-
-            // 2. 根据难度选择3个方块作为障碍
-            let obstacle_ids = self.select_obstacle_pieces(&solution, difficulty);
-            if obstacle_ids.len() != OBSTACLE_COUNT {
-                continue;
+        self.generate_with_report(difficulty, GenerationStrategy::FromSolution).level
+    }
+
+    /// 方式二：先放障碍再求解
+    pub fn generate_from_obstacles(&self, difficulty: Difficulty) -> Option<GameState> {
+        self.generate_with_report(difficulty, GenerationStrategy::FromObstacles).level
+    }
+
+    /// 为多解关卡添加最少的固定提示方块，使其只有唯一解
+    ///
+    /// 枚举关卡的所有解（最多 `MAX_CLUE_SOLUTIONS` 个），按数量从少到多寻找只被一个解包含的提示组合，
+    /// 因此提示数是所有解中最少的（解数超过上限时只在枚举到的解中取最少）。
+    /// 已是唯一解时原样返回；无解，或搜索超过 `MAX_CLUE_NODES` 个节点仍未找到时返回None。
+    pub fn add_minimal_clues(&self, level: &GameState) -> Option<GameState> {
+        let solutions = match Solver::new(MAX_CLUE_SOLUTIONS).solve(level) {
+            SolveResult::NoSolution => return None,
+            SolveResult::UniqueSolution(_) => return Some(level.clone()),
+            SolveResult::MultipleSolutions(solutions) => solutions,
+        };
+
+        let table = ClueTable::new(level, &solutions);
+        let max_count = table.solutions.iter().map(Vec::len).max().unwrap_or(0);
+        let all = vec![u64::MAX; table.holders.first().map_or(0, Vec::len)];
+
+        for count in 1..=max_count {
+            for index in 0..table.solutions.len() {
+                let mut chosen = Vec::with_capacity(count);
+                if let Some(state) = self.search_clues(&table, index, 0, count, &mut chosen, &all) {
+                    return Some(state);
+                }
+                if table.budget.get() == 0 {
+                    return None;
+                }
             }
+        }
+        None
+    }
+
+    /// 从第 `index` 个解的第 `start` 个放置起选出 `count` 个提示的所有组合，返回第一个唯一解关卡
+    ///
+    /// `shared` 为包含已选提示的解的位集。
+    fn search_clues(
+        &self,
+        table: &ClueTable,
+        index: usize,
+        start: usize,
+        count: usize,
+        chosen: &mut Vec<usize>,
+        shared: &[u64],
+    ) -> Option<GameState> {
+        if table.budget.get() == 0 {
+            return None;
+        }
+        table.budget.set(table.budget.get() - 1);
 
-            // 3. 创建新的游戏状态
-            let state = self.create_state_from_solution(&solution, &obstacle_ids);
+        if chosen.len() == count {
+            // 其他解也包含这些提示时不可能唯一，不必求解
+            let own_bit = |word: usize| if word == index / 64 { 1 << (index % 64) } else { 0 };
+            if shared.iter().enumerate().any(|(word, &bits)| bits & !own_bit(word) != 0) {
+                return None;
+            }
 
-            // 4. 验证有唯一解
-            if self.validate_unique_solution(&state) {
-                return Some(state);
+            let mut state = table.level.clone();
+            for &clue in chosen.iter() {
+                state.add_clue(table.placements[clue].clone());
             }
+            // 枚举到了全部解时位集已能断定唯一，否则还要求解确认
+            return (table.complete || self.solver.count_solutions(&state) == 1).then_some(state);
         }
 
+        let own = &table.solutions[index];
+        for i in start..own.len() {
+            if own.len() - i < count - chosen.len() {
+                break;
+            }
+            let placement = own[i];
+            let next: Vec<u64> = shared.iter().zip(&table.holders[placement]).map(|(a, b)| a & b).collect();
+            chosen.push(placement);
+            let found = self.search_clues(table, index, i + 1, count, chosen, &next);
+            chosen.pop();
+            if found.is_some() {
+                return found;
+            }
+        }
         None
     }
 
-    /// 方式二：先放障碍再求解
-    pub fn generate_from_obstacles(&self, difficulty: Difficulty) -> Option<GameState> {
-        let mut rng = rand::rng();
+    /// 从完整解反推的一次尝试
+    fn attempt_from_solution(&self, profile: &ObstacleProfile, report: &mut GenerationReport) -> Option<GameState> {
+        report.attempts += 1;
+
+        // 1. 生成一个完整解
+        let Some(solution) = self.generate_complete_solution() else {
+            report.rejections.placement_failed += 1;
+            return None;
+        };
+
+        // 2. 按障碍配置选择障碍方块
+        let obstacle_ids = self.select_obstacle_pieces(&solution, profile);
+        if obstacle_ids.len() != profile.count {
+            report.rejections.placement_failed += 1;
+            return None;
+        }
 
-        for _ in 0..self.max_retries {
-            // 1. 随机选择3个piece ID作为障碍
-            let mut all_pieces = get_standard_pieces();
-            let obstacle_piece_candidates = difficulty.get_obstacle_piece_ids();
+        // 3. 创建新的游戏状态
+        let state = self.create_state_from_solution(&solution, &obstacle_ids);
+        if !self.constraints.accepts_obstacles(&state.board) || !self.constraints.accepts_solution(&solution.board) {
+            report.rejections.constraints_failed += 1;
+            return None;
+        }
 
-            if obstacle_piece_candidates.len() < OBSTACLE_COUNT {
-                continue;
-            }
+        // 4. 验证有唯一解（唯一解就是上面的完整解）
+        self.validate_unique_solution(&state, report)?;
+        report.strategy = Some(GenerationStrategy::FromSolution);
+        Some(state)
+    }
 
-            let selected_ids: Vec<u8> = obstacle_piece_candidates
-                .choose_multiple(&mut rng, OBSTACLE_COUNT)
-                .copied()
-                .collect();
+    /// 先放障碍方式的初始阶段
+    fn obstacles_phase(&self, profile: &ObstacleProfile) -> JobPhase {
+        // 障碍很少时随机摆放几乎碰不到唯一解，改为逐个检查所有布局（打乱顺序、随机对称变换）
+        if profile.count >= OBSTACLE_COUNT {
+            return JobPhase::FromObstacles { remaining: self.max_retries };
+        }
+        if profile.candidates.len() < profile.count {
+            return JobPhase::Done;
+        }
 
-            // 2. 随机放置这些障碍
-            let (board, obstacle_positions) = match self.random_place_obstacles(&selected_ids) {
-                Some(result) => result,
-                None => continue,
-            };
+        let mut rng = self.rng.borrow_mut();
+        let selected_ids: Vec<u8> = profile.candidates.choose_multiple(&mut *rng, profile.count).copied().collect();
+        let mut layouts = enumerate_obstacle_layouts(&selected_ids);
+        layouts.shuffle(&mut *rng);
+        let symmetries: Vec<usize> = layouts.iter().map(|_| rng.random_range(0..8)).collect();
+        JobPhase::Layouts {
+            layouts: layouts.into_iter().zip(symmetries).collect::<Vec<_>>().into_iter(),
+        }
+    }
 
-            // 3. 创建游戏状态（移除障碍piece）
-            all_pieces.retain(|p| !selected_ids.contains(&p.id));
+    /// 先放障碍再求解的一次尝试
+    fn attempt_from_obstacles(&self, profile: &ObstacleProfile, report: &mut GenerationReport) -> Option<GameState> {
+        report.attempts += 1;
 
-            let mut state = GameState::new(all_pieces);
-            state.board = board;
-            state.obstacle_positions = obstacle_positions;
+        // 1. 按障碍配置随机选择障碍方块
+        if profile.candidates.len() < profile.count {
+            report.rejections.placement_failed += 1;
+            return None;
+        }
 
-            // 4. 验证有唯一解
-            if self.validate_unique_solution(&state) {
-                return Some(state);
-            }
+        let selected_ids: Vec<u8> = profile
+            .candidates
+            .choose_multiple(&mut *self.rng.borrow_mut(), profile.count)
+            .copied()
+            .collect();
+
+        // 2. 随机放置这些障碍
+        let Some((board, obstacle_positions)) = self.random_place_obstacles(&selected_ids) else {
+            report.rejections.placement_failed += 1;
+            return None;
+        };
+
+        // 3. 创建游戏状态（移除障碍piece）
+        let mut all_pieces = get_standard_pieces();
+        all_pieces.retain(|p| !selected_ids.contains(&p.id));
+
+        let mut state = GameState::new(all_pieces);
+        state.board = board;
+        state.obstacle_positions = obstacle_positions;
+
+        // 4. 验证有唯一解，并检查解是否满足约束
+        self.accept_unique(state, report)
+    }
+
+    /// 检查一个枚举出的障碍布局
+    fn attempt_layout(&self, board: Board, report: &mut GenerationReport) -> Option<GameState> {
+        report.attempts += 1;
+        if !self.constraints.accepts_obstacles(&board) {
+            report.rejections.constraints_failed += 1;
+            return None;
         }
+        self.accept_unique(state_from_obstacle_board(board), report)
+    }
 
-        None
+    /// 关卡有唯一解且解满足约束时接受
+    pub(crate) fn accept_unique(&self, state: GameState, report: &mut GenerationReport) -> Option<GameState> {
+        let solution = self.validate_unique_solution(&state, report)?;
+        if !self.constraints.accepts_solution(&solution.board) {
+            report.rejections.constraints_failed += 1;
+            return None;
+        }
+        report.strategy = Some(GenerationStrategy::FromObstacles);
+        Some(state)
     }
 
     /// 生成一个完整解（填满整个8x8棋盘）
-    fn generate_complete_solution(&self) -> Option<Solution> {
-        let mut rng = rand::rng();
-        let mut pieces = get_standard_pieces();
+    pub(crate) fn generate_complete_solution(&self) -> Option<Solution> {
+        self.complete_solution(Board::new(), get_standard_pieces())
+    }
+
+    /// 用给定方块随机填满棋盘上的空格
+    pub(crate) fn complete_solution(&self, board: Board, mut pieces: Vec<Piece>) -> Option<Solution> {
+        let mut rng = self.rng.borrow_mut();
 
         // 随机打乱方块顺序，增加多样性
-        pieces.shuffle(&mut rng);
+        pieces.shuffle(&mut *rng);
 
         // 随机决定某些方块是否旋转
         for piece in &mut pieces {
@@ -108,8 +530,10 @@ impl Generator {
                 piece.rotate();
             }
         }
+        drop(rng);
 
-        let state = GameState::new(pieces);
+        let mut state = GameState::new(pieces);
+        state.board = board;
         let solver = Solver::new(1);
 
         match solver.solve(&state) {
@@ -120,27 +544,32 @@ impl Generator {
     }
 
     /// 从完整解中选择障碍方块
-    fn select_obstacle_pieces(&self, solution: &Solution, difficulty: Difficulty) -> Vec<u8> {
-        let mut rng = rand::rng();
-        let candidate_ids = difficulty.get_obstacle_piece_ids();
+    pub(crate) fn select_obstacle_pieces(&self, solution: &Solution, profile: &ObstacleProfile) -> Vec<u8> {
+        let mut rng = self.rng.borrow_mut();
 
-        // 从解中找出符合难度的方块
+        // 从解中找出候选方块
         let available: Vec<u8> = solution.placements
             .iter()
-            .filter(|p| candidate_ids.contains(&p.piece_id))
+            .filter(|p| profile.candidates.contains(&p.piece_id))
             .map(|p| p.piece_id)
             .collect();
 
-        // 随机选择3个
+        // 随机选择配置数量的方块
         available
-            .choose_multiple(&mut rng, OBSTACLE_COUNT.min(available.len()))
+            .choose_multiple(&mut *rng, profile.count.min(available.len()))
             .copied()
             .collect()
     }
 
     /// 从解和障碍ID创建游戏状态
-    fn create_state_from_solution(&self, solution: &Solution, obstacle_ids: &[u8]) -> GameState {
+    pub(crate) fn create_state_from_solution(&self, solution: &Solution, obstacle_ids: &[u8]) -> GameState {
+        // 空洞保留，其余格子清空
         let mut board = Board::new();
+        for (cell, &solved) in board.cells_mut().iter_mut().zip(solution.board.cells()) {
+            if solved == VOID_CELL {
+                *cell = VOID_CELL;
+            }
+        }
         let mut obstacle_positions = Vec::new();
 
         // 只放置障碍方块到棋盘
@@ -162,10 +591,11 @@ impl Generator {
             }
         }
 
-        // 创建剩余的方块列表
+        // 创建剩余的方块列表（只含解中用到的方块）
         let pieces: Vec<Piece> = get_standard_pieces()
             .into_iter()
             .filter(|p| !obstacle_ids.contains(&p.id))
+            .filter(|p| solution.placements.iter().any(|placement| placement.piece_id == p.id))
             .collect();
 
         let mut state = GameState::new(pieces);
@@ -177,8 +607,8 @@ impl Generator {
 
     /// 随机放置障碍方块
     /// 返回：(棋盘, 障碍位置列表)
-    fn random_place_obstacles(&self, piece_ids: &[u8]) -> Option<(Board, Vec<(usize, usize, u8)>)> {
-        let mut rng = rand::rng();
+    fn random_place_obstacles(&self, piece_ids: &[u8]) -> Option<(Board, ObstaclePositions)> {
+        let mut rng = self.rng.borrow_mut();
         let mut board = Board::new();
         let mut positions = Vec::new();
 
@@ -198,7 +628,7 @@ impl Generator {
                 let row = rng.random_range(0..8);
                 let col = rng.random_range(0..8);
 
-                if board.can_place(&piece, row, col) {
+                if board.can_place(&piece, row, col) && self.constraints.allows_obstacle(&piece, row, col) {
                     board.place(&piece, row, col);
 
                     // 记录障碍位置
@@ -225,15 +655,108 @@ impl Generator {
         Some((board, positions))
     }
 
-    /// 验证关卡有唯一解
-    fn validate_unique_solution(&self, state: &GameState) -> bool {
+    /// 验证关卡有唯一解并返回该解，不唯一时记录拒绝原因
+    pub(crate) fn validate_unique_solution(&self, state: &GameState, report: &mut GenerationReport) -> Option<Solution> {
         match self.solver.solve(state) {
-            SolveResult::UniqueSolution(_) => true,
-            _ => false,
+            SolveResult::UniqueSolution(solution) => Some(solution),
+            SolveResult::NoSolution => {
+                report.rejections.no_solution += 1;
+                None
+            }
+            SolveResult::MultipleSolutions(_) => {
+                report.rejections.not_unique += 1;
+                None
+            }
         }
     }
 }
 
+/// 用障碍布局创建关卡
+///
+/// 棋盘上的负数格子视为障碍（-id），剩余方块为未作为障碍的标准方块。
+pub fn state_from_obstacle_board(board: Board) -> GameState {
+    let mut obstacle_positions: ObstaclePositions = Vec::new();
+    for (idx, &cell) in board.cells().iter().enumerate() {
+        if cell < 0 && cell != VOID_CELL {
+            let id = cell.unsigned_abs();
+            if !obstacle_positions.iter().any(|&(_, _, existing)| existing == id) {
+                obstacle_positions.push((idx / BOARD_SIZE, idx % BOARD_SIZE, id));
+            }
+        }
+    }
+
+    let pieces: Vec<Piece> = get_standard_pieces()
+        .into_iter()
+        .filter(|p| !obstacle_positions.iter().any(|&(_, _, id)| id == p.id))
+        .collect();
+
+    let mut state = GameState::new(pieces);
+    state.board = board;
+    state.obstacle_positions = obstacle_positions;
+    state
+}
+
+/// 枚举指定障碍方块的所有摆放方式，每个对称类只保留一个
+pub fn enumerate_obstacle_layouts(piece_ids: &[u8]) -> Vec<Board> {
+    let pieces: Vec<Piece> = piece_ids
+        .iter()
+        .filter_map(|&id| crate::piece::get_piece_by_id(id))
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut layouts = Vec::new();
+    enumerate_layouts(&mut Board::new(), &pieces, Board::canonical, &mut seen, &mut layouts);
+    layouts
+}
+
+/// 在带空洞的棋盘上枚举障碍方块的所有摆放方式（空洞一般不对称，所以不按对称类去重）
+pub(crate) fn enumerate_layouts_on(board: &Board, pieces: &[Piece]) -> Vec<Board> {
+    let mut seen = HashSet::new();
+    let mut layouts = Vec::new();
+    enumerate_layouts(&mut board.clone(), pieces, Board::clone, &mut seen, &mut layouts);
+    layouts
+}
+
+/// `key` 决定哪些布局算同一种
+fn enumerate_layouts(
+    board: &mut Board,
+    pieces: &[Piece],
+    key: fn(&Board) -> Board,
+    seen: &mut HashSet<Board>,
+    layouts: &mut Vec<Board>,
+) {
+    let Some((first, rest)) = pieces.split_first() else {
+        if seen.insert(key(board)) {
+            layouts.push(board.clone());
+        }
+        return;
+    };
+
+    let mut piece = first.clone();
+    let orientations = if piece.width == piece.height { 1 } else { 2 };
+    for _ in 0..orientations {
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                if !board.can_place(&piece, row, col) {
+                    continue;
+                }
+                for r in row..row + piece.height {
+                    for c in col..col + piece.width {
+                        board.set(r, c, -(piece.id as i8));
+                    }
+                }
+                enumerate_layouts(board, rest, key, seen, layouts);
+                for r in row..row + piece.height {
+                    for c in col..col + piece.width {
+                        board.set(r, c, 0);
+                    }
+                }
+            }
+        }
+        piece.rotate();
+    }
+}
+
 impl Default for Generator {
     fn default() -> Self {
         Self::new()
@@ -243,6 +766,15 @@ impl Default for Generator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_parse_difficulty_and_strategy() {
+        assert_eq!("hard".parse(), Ok(Difficulty::Hard));
+        assert_eq!("obstacles".parse(), Ok(GenerationStrategy::FromObstacles));
+        assert_eq!("Hard".parse::<Difficulty>().unwrap_err().to_string(), "Invalid difficulty: Hard");
+        assert!("".parse::<GenerationStrategy>().is_err());
+    }
 
     #[test]
     fn test_generate_complete_solution() {
@@ -266,8 +798,8 @@ mod tests {
         let state = generator.generate_from_solution(Difficulty::Easy);
 
         if let Some(s) = state {
-            // 验证有8个可用方块（11 - 3个障碍）
-            assert_eq!(s.pieces.len(), 8);
+            // 简单难度预放5个方块，剩6个可用
+            assert_eq!(s.pieces.len(), 6);
 
             // 验证棋盘上有障碍
             let obstacle_count = s.board.cells().iter().filter(|&&c| c < 0).count();
@@ -275,6 +807,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_seeded_generation_is_deterministic() {
+        let a = Generator::with_seed(42).generate(Difficulty::Medium);
+        let b = Generator::with_seed(42).generate(Difficulty::Medium);
+
+        assert_eq!(a.map(|s| s.board), b.map(|s| s.board));
+    }
+
+    #[test]
+    fn test_enumerate_obstacle_layouts() {
+        // 1x1: 64个位置，8x8棋盘的对称类为10个
+        assert_eq!(enumerate_obstacle_layouts(&[1]).len(), 10);
+
+        let layouts = enumerate_obstacle_layouts(&[1, 2]);
+        let mut canonical: Vec<Board> = layouts.iter().map(|b| b.canonical()).collect();
+        canonical.sort_by(|a, b| a.cells().cmp(b.cells()));
+        canonical.dedup();
+        assert_eq!(canonical.len(), layouts.len());
+
+        let state = state_from_obstacle_board(layouts[0].clone());
+        assert_eq!(state.pieces.len(), 9);
+        assert_eq!(state.obstacle_positions.len(), 2);
+    }
+
     #[test]
     fn test_generate_from_obstacles() {
         let generator = Generator::new();
@@ -283,10 +839,160 @@ mod tests {
             let state = generator.generate_from_obstacles(difficulty);
 
             if let Some(s) = state {
-                assert_eq!(s.pieces.len(), 8);
+                assert_eq!(s.pieces.len(), 11 - difficulty.obstacle_profile().count);
                 let obstacle_count = s.board.cells().iter().filter(|&&c| c < 0).count();
                 assert!(obstacle_count > 0);
             }
         }
     }
+
+    #[test]
+    fn test_difficulty_profiles_generate_unique_levels() {
+        let generator = Generator::with_seed(5);
+
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let profile = difficulty.obstacle_profile();
+            let level = generator.generate(difficulty).expect("Every profile should generate a level");
+
+            assert_eq!(level.obstacle_positions.len(), profile.count);
+            assert!(level.obstacle_positions.iter().all(|(_, _, id)| profile.candidates.contains(id)));
+            assert_eq!(level.pieces.len() + profile.count, 11);
+            assert_eq!(Solver::new(2).count_solutions(&level), 1);
+        }
+
+        // 自定义配置：只用1x1和1x2
+        let outcome = generator.generate_with_profile(&ObstacleProfile::new(2, &[1, 2]), GenerationStrategy::Auto);
+        let level = outcome.level.unwrap();
+        assert_eq!(level.board.cells().iter().filter(|&&c| c < 0).count(), 3);
+    }
+
+    #[test]
+    fn test_add_minimal_clues() {
+        let level = state_from_obstacle_board(crate::board!(
+            "# # # . . . . ."
+            ". . . . . . . ."
+            ". . . . . . . ."
+            ". . . . . . . ."
+            ". . . . . . . ."
+            ". . . . . . . ."
+            ". . . . . . # #"
+            ". . . . . . . #"
+        ));
+        let solver = Solver::new(2);
+        assert!(solver.count_solutions(&level) > 1);
+
+        let generator = Generator::with_seed(1);
+        let clued = generator.add_minimal_clues(&level).unwrap();
+        assert!(!clued.clues.is_empty());
+        assert_eq!(solver.count_solutions(&clued), 1);
+        assert!(clued.clues.iter().all(|c| clued.board.cells().contains(&(c.piece_id as i8))));
+        assert_eq!(clued.used_pieces.iter().filter(|&&u| u).count(), clued.clues.len());
+
+        // 暴力检查：任何解的任何少一个提示的组合都不唯一
+        let SolveResult::MultipleSolutions(solutions) = Solver::new(MAX_CLUE_SOLUTIONS).solve(&level) else {
+            panic!("Expected multiple solutions");
+        };
+        let fewer = clued.clues.len() - 1;
+        for solution in &solutions {
+            let n = solution.placements.len();
+            for mask in (0u32..1 << n).filter(|mask| mask.count_ones() as usize == fewer) {
+                let mut state = level.clone();
+                for (i, placement) in solution.placements.iter().enumerate() {
+                    if mask & (1 << i) != 0 {
+                        assert!(state.add_clue(placement.clone()));
+                    }
+                }
+                assert!(solver.count_solutions(&state) > 1, "{:?} should not be unique", state.clues);
+            }
+        }
+
+        // 已唯一的关卡不需要提示
+        let unique = Generator::with_seed(3).generate(Difficulty::Medium).unwrap();
+        assert!(generator.add_minimal_clues(&unique).unwrap().clues.is_empty());
+    }
+
+    #[test]
+    fn test_add_minimal_clues_many_solutions() {
+        // 只有一个障碍的棋盘解数远超枚举上限，搜索必须在有限时间内结束
+        let level = state_from_obstacle_board(crate::board!(
+            "# . . . . . . ."
+            ". . . . . . . ."
+            ". . . . . . . ."
+            ". . . . . . . ."
+            ". . . . . . . ."
+            ". . . . . . . ."
+            ". . . . . . . ."
+            ". . . . . . . ."
+        ));
+        let clued = Generator::with_seed(1).add_minimal_clues(&level).expect("Clues should be found within the budget");
+        assert!(!clued.clues.is_empty());
+        assert_eq!(Solver::new(2).count_solutions(&clued), 1);
+    }
+
+    #[test]
+    fn test_generate_with_report() {
+        for strategy in [GenerationStrategy::Auto, GenerationStrategy::FromSolution, GenerationStrategy::FromObstacles] {
+            let outcome = Generator::with_seed(3).generate_with_report(Difficulty::Medium, strategy);
+            let report = &outcome.report;
+
+            assert!(outcome.level.is_some());
+            assert_eq!(report.requested, strategy);
+            match strategy {
+                GenerationStrategy::Auto => assert!(report.strategy.is_some()),
+                _ => assert_eq!(report.strategy, Some(strategy)),
+            }
+            // 每次尝试要么成功（最后一次），要么记录一个拒绝原因
+            assert_eq!(report.rejections.total(), report.attempts - 1);
+        }
+    }
+
+    #[test]
+    fn test_report_counts_failures() {
+        let mut generator = Generator::with_seed(3);
+        generator.max_retries = 2;
+        generator.solver = Solver::new(0); // 任何关卡都会被判为无解
+
+        let outcome = generator.generate_with_report(Difficulty::Easy, GenerationStrategy::Auto);
+        assert!(outcome.level.is_none());
+        assert_eq!(outcome.report.strategy, None);
+        assert_eq!(outcome.report.attempts, 4);
+        assert_eq!(outcome.report.rejections.total(), 4);
+    }
+
+    #[test]
+    fn test_stepped_job_matches_one_shot_generation() {
+        for (difficulty, strategy) in [
+            (Difficulty::Hard, GenerationStrategy::Auto),
+            (Difficulty::Easy, GenerationStrategy::FromObstacles),
+        ] {
+            let expected = Generator::with_seed(11).generate_with_report(difficulty, strategy);
+
+            let generator = Generator::with_seed(11);
+            let mut job = generator.start_job(&difficulty.obstacle_profile(), strategy);
+            let mut steps = 0;
+            while !generator.advance(&mut job, 1) {
+                steps += 1;
+                assert_eq!(job.report().attempts, steps);
+            }
+            assert!(job.is_finished());
+
+            let outcome = job.into_outcome();
+            assert_eq!(outcome.level.map(|l| l.board), expected.level.map(|l| l.board));
+            assert_eq!(outcome.report.attempts, expected.report.attempts);
+            assert_eq!(outcome.report.rejections, expected.report.rejections);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(8))]
+
+        #[test]
+        fn prop_generated_levels_are_unique(seed in any::<u64>()) {
+            let generator = Generator::with_seed(seed);
+            if let Some(level) = generator.generate(Difficulty::Medium) {
+                prop_assert_eq!(Solver::new(2).count_solutions(&level), 1);
+                prop_assert_eq!(crate::testing::reference_count(&level, 2), 1);
+            }
+        }
+    }
 }
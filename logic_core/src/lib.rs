@@ -4,25 +4,58 @@
 
 pub mod types;
 pub mod board;
+pub mod render;
+pub mod parse;
+pub mod export;
 pub mod piece;
 pub mod solver;
 pub mod generator;
+pub mod constraints;
+pub mod session;
+pub mod verify;
+pub mod replay;
+pub mod pack;
+pub mod book;
+pub mod reduce;
+pub mod voids;
+pub mod shape;
+pub mod model;
+
+// 测试工具（proptest策略、参考求解器）
+#[cfg(test)]
+mod testing;
 
 // WASM绑定（仅在编译为WASM时包含）
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
 
 // 重新导出常用类型
-pub use types::{Board, Piece, GameState, Color, Difficulty, Solution, Placement, SolveResult};
-pub use solver::Solver;
-pub use generator::Generator;
+pub use types::{Board, Piece, GameState, Color, Difficulty, ObstacleProfile, ParseNameError, Solution, Placement, SolveResult};
+pub use solver::{BranchingStrategy, SearchStats, Solver};
+pub use generator::{GenerationJob, GenerationOutcome, GenerationReport, GenerationStrategy, Generator, RejectionCounts};
+pub use constraints::{ConstraintError, GenerationConstraints, Orientation};
+pub use session::{Session, SessionError};
+pub use render::{RenderCharset, RenderOptions};
+pub use export::ExportOptions;
+pub use parse::ParseBoardError;
+pub use pack::{LevelPack, PackLevel, UnlockRule};
+pub use book::{BookEntry, PuzzleBook};
+pub use reduce::Reduction;
+pub use voids::VoidPattern;
+pub use shape::BoardMask;
+pub use model::{ModelError, PlacementModel};
+pub use replay::{Replay, ReplayOrder};
+pub use verify::{verify_solution, VerifiedSolution, VerificationError};
 
 /// 棋盘大小常量
 pub const BOARD_SIZE: usize = 8;
 pub const TOTAL_CELLS: usize = BOARD_SIZE * BOARD_SIZE;
 
-/// 障碍块数量
+/// 标准障碍块数量（三个黑色块，中等难度）
 pub const OBSTACLE_COUNT: usize = 3;
 
 /// 总方块数量
 pub const TOTAL_PIECES: usize = 11;
+
+/// 空洞格子：不属于棋盘，任何方块都不覆盖（与障碍的负数ID区分）
+pub const VOID_CELL: i8 = i8::MIN;
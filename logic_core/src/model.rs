@@ -0,0 +1,493 @@
+//! 求解模型导出模块 - 把关卡转成 DIMACS CNF 和 LP/MPS 模型，交给外部 SAT/ILP 求解器交叉验证
+//!
+//! 每个变量对应一个候选放置（方块、朝向、左上角位置），取1表示采用这个放置：
+//! - 每个空格恰好被一个放置覆盖
+//! - 每个方块最多用一次（方块总面积等于空格数时恰好一次）
+//! - 同种方块按顺序使用：用了后一个就必须用前一个，且后一个的位置在前一个之后，
+//!   和 `Solver` 一样不把互换同种方块算作不同的解
+//!
+//! 约束都按两两互斥编码，不引入辅助变量，所以模型的解与关卡的解一一对应。
+
+use std::collections::HashSet;
+use std::fmt::{self, Write};
+
+use crate::types::{GameState, Placement, Solution};
+use crate::verify::{verify_solution, VerificationError};
+use crate::BOARD_SIZE;
+
+/// 导入外部求解结果失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelError {
+    /// 求解器报告无解
+    Unsatisfiable,
+    /// 无法解析的内容
+    Parse(String),
+    /// 变量编号超出模型范围
+    UnknownVariable(i64),
+    /// 解中的放置不在模型中
+    UnknownPlacement(u8),
+    /// 采用的放置互相重叠
+    Overlap { row: usize, col: usize },
+    /// 还原出的棋盘不是合法解
+    Invalid(VerificationError),
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelError::Unsatisfiable => write!(f, "Model is unsatisfiable"),
+            ModelError::Parse(line) => write!(f, "Cannot parse solver output: {}", line),
+            ModelError::UnknownVariable(var) => write!(f, "Unknown variable {}", var),
+            ModelError::UnknownPlacement(id) => write!(f, "Placement of piece {} is not in the model", id),
+            ModelError::Overlap { row, col } => write!(f, "Placements overlap at ({}, {})", row, col),
+            ModelError::Invalid(e) => write!(f, "Assignment is not a solution: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+/// 线性约束：(名称, (变量, 系数), 关系, 右端)，关系为 "=" 或 "<="
+type Row = (String, Vec<(usize, i64)>, &'static str, i64);
+
+/// 关卡的放置模型
+#[derive(Debug, Clone)]
+pub struct PlacementModel {
+    /// 候选放置，变量编号 = 下标 + 1
+    placements: Vec<Placement>,
+    /// 每个空格：覆盖它的放置
+    cells: Vec<((usize, usize), Vec<usize>)>,
+    /// 每个方块：它的所有放置
+    pieces: Vec<(u8, Vec<usize>)>,
+    /// 方块是否必须恰好用一次（否则最多一次）
+    exact_pieces: bool,
+    /// 不能同时采用的放置对（同种方块的顺序约束）
+    conflicts: Vec<(usize, usize)>,
+    /// 同种方块对 (后一个, 前一个) 在 `pieces` 中的下标：用了后一个就必须用前一个
+    twins: Vec<(usize, usize)>,
+    /// 被排除的解（每个解是一组放置，不能全部采用）
+    excluded: Vec<Vec<usize>>,
+}
+
+impl PlacementModel {
+    /// 从关卡构建模型（只包含托盘中未使用的方块）
+    pub fn new(level: &GameState) -> Self {
+        let mut placements = Vec::new();
+        let mut pieces = Vec::new();
+        let remaining: Vec<_> = level.pieces.iter().zip(&level.used_pieces).filter(|(_, used)| !**used).map(|(p, _)| p).collect();
+
+        for piece in &remaining {
+            let mut options = Vec::new();
+            let mut oriented = (*piece).clone();
+            oriented.reset();
+            let orientations = if oriented.width == oriented.height { 1 } else { 2 };
+            for rotation in 0..orientations {
+                if rotation == 1 {
+                    oriented.rotate();
+                }
+                for row in 0..BOARD_SIZE {
+                    for col in 0..BOARD_SIZE {
+                        if level.board.can_place(&oriented, row, col) {
+                            options.push(placements.len());
+                            placements.push(Placement { piece_id: piece.id, row, col, rotated: oriented.rotated });
+                        }
+                    }
+                }
+            }
+            // 按位置排序，方便同种方块的顺序约束
+            options.sort_by_key(|&var| (placements[var].row, placements[var].col));
+            pieces.push((piece.id, options));
+        }
+
+        let mut cells = Vec::new();
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                if level.board.is_empty(row, col) {
+                    let covering = (0..placements.len()).filter(|&var| covers(level, &placements[var], row, col)).collect();
+                    cells.push(((row, col), covering));
+                }
+            }
+        }
+
+        // 同种方块：后一个的位置必须严格在前一个之后（行优先）
+        let mut conflicts = Vec::new();
+        let mut twins = Vec::new();
+        for i in 0..remaining.len() {
+            let Some(j) = (0..i).rev().find(|&j| remaining[j].same_kind(remaining[i])) else {
+                continue;
+            };
+            twins.push((i, j));
+            for &a in &pieces[j].1 {
+                for &b in &pieces[i].1 {
+                    let (pa, pb) = (&placements[a], &placements[b]);
+                    if (pb.row, pb.col) <= (pa.row, pa.col) {
+                        conflicts.push((a, b));
+                    }
+                }
+            }
+        }
+
+        let total_area: usize = remaining.iter().map(|p| p.area()).sum();
+        Self {
+            placements,
+            cells,
+            pieces,
+            exact_pieces: total_area == level.board.count_empty(),
+            conflicts,
+            twins,
+            excluded: Vec::new(),
+        }
+    }
+
+    /// 变量数量
+    pub fn variable_count(&self) -> usize {
+        self.placements.len()
+    }
+
+    /// 变量对应的放置（变量编号从1开始）
+    pub fn placement(&self, var: usize) -> Option<&Placement> {
+        var.checked_sub(1).and_then(|idx| self.placements.get(idx))
+    }
+
+    /// 排除一个已知的解（再次求解无解即说明该解唯一）
+    ///
+    /// 没有放置的解（棋盘已填满）被排除后模型无解：CNF 中是空子句，LP 中是 `0 <= -1`。
+    pub fn exclude(&mut self, solution: &Solution) -> Result<(), ModelError> {
+        let vars = solution
+            .placements
+            .iter()
+            .map(|p| self.placements.iter().position(|q| q == p).ok_or(ModelError::UnknownPlacement(p.piece_id)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.excluded.push(vars);
+        Ok(())
+    }
+
+    /// 所有子句（变量编号从1开始，负数表示取反）
+    fn clauses(&self) -> Vec<Vec<i64>> {
+        let var = |idx: usize| idx as i64 + 1;
+        let mut clauses = Vec::new();
+        let at_most_one = |vars: &[usize], clauses: &mut Vec<Vec<i64>>| {
+            for (k, &a) in vars.iter().enumerate() {
+                for &b in &vars[k + 1..] {
+                    clauses.push(vec![-var(a), -var(b)]);
+                }
+            }
+        };
+
+        for (_, covering) in &self.cells {
+            clauses.push(covering.iter().map(|&idx| var(idx)).collect());
+            at_most_one(covering, &mut clauses);
+        }
+        for (_, options) in &self.pieces {
+            if self.exact_pieces {
+                clauses.push(options.iter().map(|&idx| var(idx)).collect());
+            }
+            at_most_one(options, &mut clauses);
+        }
+        for &(a, b) in &self.conflicts {
+            clauses.push(vec![-var(a), -var(b)]);
+        }
+        for &(i, j) in &self.twins {
+            for &b in &self.pieces[i].1 {
+                let mut clause = vec![-var(b)];
+                clause.extend(self.pieces[j].1.iter().map(|&a| var(a)));
+                clauses.push(clause);
+            }
+        }
+        for vars in &self.excluded {
+            clauses.push(vars.iter().map(|&idx| -var(idx)).collect());
+        }
+        clauses
+    }
+
+    /// 导出 DIMACS CNF（注释行列出每个变量对应的放置）
+    pub fn to_dimacs(&self) -> String {
+        let clauses = self.clauses();
+        let mut out = String::new();
+        let _ = writeln!(out, "c logic-puzzle placement model");
+        let _ = writeln!(out, "c var piece_id row col rotated");
+        for (idx, p) in self.placements.iter().enumerate() {
+            let _ = writeln!(out, "c {} {} {} {} {}", idx + 1, p.piece_id, p.row, p.col, p.rotated as u8);
+        }
+        let _ = writeln!(out, "p cnf {} {}", self.placements.len(), clauses.len());
+        for clause in clauses {
+            for lit in clause {
+                let _ = write!(out, "{} ", lit);
+            }
+            out.push_str("0\n");
+        }
+        out
+    }
+
+    /// 所有线性约束
+    fn rows(&self) -> Vec<Row> {
+        let ones = |vars: &[usize]| vars.iter().map(|&idx| (idx, 1)).collect::<Vec<_>>();
+        let mut rows = Vec::new();
+        for ((row, col), covering) in &self.cells {
+            rows.push((format!("cell_{}_{}", row, col), ones(covering), "=", 1));
+        }
+        for (id, options) in &self.pieces {
+            rows.push((format!("piece_{}", id), ones(options), if self.exact_pieces { "=" } else { "<=" }, 1));
+        }
+        for (k, &(a, b)) in self.conflicts.iter().enumerate() {
+            rows.push((format!("order_{}", k), ones(&[a, b]), "<=", 1));
+        }
+        for (k, &(i, j)) in self.twins.iter().enumerate() {
+            let mut terms = ones(&self.pieces[i].1);
+            terms.extend(self.pieces[j].1.iter().map(|&idx| (idx, -1)));
+            rows.push((format!("twin_{}", k), terms, "<=", 0));
+        }
+        for (k, vars) in self.excluded.iter().enumerate() {
+            rows.push((format!("exclude_{}", k), ones(vars), "<=", vars.len() as i64 - 1));
+        }
+        rows
+    }
+
+    /// 导出 CPLEX LP 格式（变量名 x1, x2, ... 与 DIMACS 编号一致）
+    pub fn to_lp(&self) -> String {
+        let mut out = String::from("\\ logic-puzzle placement model\nMinimize\n obj: 0 x1\nSubject To\n");
+        for (name, terms, relation, rhs) in self.rows() {
+            // 系数只有 1 和 -1
+            let lhs: String = terms
+                .iter()
+                .map(|&(idx, coef)| format!(" {} x{}", if coef < 0 { "-" } else { "+" }, idx + 1))
+                .collect();
+            let lhs = if terms.is_empty() { "0 x1" } else { lhs.trim_start_matches(" + ").trim_start() };
+            let _ = writeln!(out, " {}: {} {} {}", name, lhs, relation, rhs);
+        }
+        out.push_str("Binary\n");
+        for idx in 0..self.placements.len() {
+            let _ = writeln!(out, " x{}", idx + 1);
+        }
+        out.push_str("End\n");
+        out
+    }
+
+    /// 导出自由格式 MPS
+    pub fn to_mps(&self) -> String {
+        let rows = self.rows();
+        let mut out = String::from("NAME logic_puzzle\nROWS\n N obj\n");
+        for (name, _, relation, _) in &rows {
+            let _ = writeln!(out, " {} {}", if *relation == "=" { "E" } else { "L" }, name);
+        }
+
+        out.push_str("COLUMNS\n    MARKER 'MARKER' 'INTORG'\n");
+        for idx in 0..self.placements.len() {
+            let _ = writeln!(out, "    x{} obj 0", idx + 1);
+            for (name, terms, _, _) in &rows {
+                if let Some((_, coef)) = terms.iter().find(|&&(var, _)| var == idx) {
+                    let _ = writeln!(out, "    x{} {} {}", idx + 1, name, coef);
+                }
+            }
+        }
+        out.push_str("    MARKER 'MARKER' 'INTEND'\nRHS\n");
+        for (name, _, _, rhs) in &rows {
+            let _ = writeln!(out, "    rhs {} {}", name, rhs);
+        }
+        out.push_str("BOUNDS\n");
+        for idx in 0..self.placements.len() {
+            let _ = writeln!(out, " BV bnd x{}", idx + 1);
+        }
+        out.push_str("ENDATA\n");
+        out
+    }
+
+    /// 读取 SAT 求解器的输出（`s SATISFIABLE` / `v 1 -2 3 ... 0`，也接受只有文字的行）
+    pub fn solution_from_dimacs(&self, level: &GameState, output: &str) -> Result<Solution, ModelError> {
+        let mut chosen = Vec::new();
+        for line in output.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('c') {
+                continue;
+            }
+            if let Some(status) = line.strip_prefix('s') {
+                if status.trim().starts_with("UNSAT") {
+                    return Err(ModelError::Unsatisfiable);
+                }
+                continue;
+            }
+            let literals = line.strip_prefix('v').unwrap_or(line);
+            for token in literals.split_whitespace() {
+                let lit: i64 = token.parse().map_err(|_| ModelError::Parse(line.to_string()))?;
+                if lit > 0 {
+                    chosen.push(lit);
+                }
+            }
+        }
+        self.solution_from_assignment(level, &chosen)
+    }
+
+    /// 读取 ILP 求解器输出的变量取值（每行中 `x<编号>` 后的第一个数是取值，大于0.5视为1）
+    pub fn solution_from_values(&self, level: &GameState, output: &str) -> Result<Solution, ModelError> {
+        let mut chosen = Vec::new();
+        for line in output.lines() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let Some(pos) = tokens.iter().position(|t| t.strip_prefix('x').is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))) else {
+                continue;
+            };
+            let var: i64 = tokens[pos][1..].parse().map_err(|_| ModelError::Parse(line.to_string()))?;
+            let value = tokens[pos + 1..].iter().find_map(|t| t.parse::<f64>().ok()).ok_or_else(|| ModelError::Parse(line.to_string()))?;
+            if value > 0.5 {
+                chosen.push(var);
+            }
+        }
+        self.solution_from_assignment(level, &chosen)
+    }
+
+    /// 把取值为1的变量还原成解，并用 [`verify_solution`] 校验
+    pub fn solution_from_assignment(&self, level: &GameState, chosen: &[i64]) -> Result<Solution, ModelError> {
+        let mut board = level.board.clone();
+        let mut seen = HashSet::new();
+        for &var in chosen {
+            let placement = usize::try_from(var).ok().and_then(|v| self.placement(v)).ok_or(ModelError::UnknownVariable(var))?;
+            if !seen.insert(var) {
+                continue;
+            }
+            let mut piece = level
+                .pieces
+                .iter()
+                .find(|p| p.id == placement.piece_id)
+                .cloned()
+                .ok_or(ModelError::UnknownPlacement(placement.piece_id))?;
+            piece.reset();
+            if placement.rotated {
+                piece.rotate();
+            }
+            if !board.can_place(&piece, placement.row, placement.col) {
+                let (row, col) = (placement.row..placement.row + piece.height)
+                    .flat_map(|r| (placement.col..placement.col + piece.width).map(move |c| (r, c)))
+                    .find(|&(r, c)| !board.is_empty(r, c))
+                    .unwrap_or((placement.row, placement.col));
+                return Err(ModelError::Overlap { row, col });
+            }
+            board.place(&piece, placement.row, placement.col);
+        }
+
+        verify_solution(level, &board).map(|v| v.solution).map_err(ModelError::Invalid)
+    }
+}
+
+/// 放置是否覆盖 (row, col)
+fn covers(level: &GameState, placement: &Placement, row: usize, col: usize) -> bool {
+    let Some(piece) = level.pieces.iter().find(|p| p.id == placement.piece_id) else {
+        return false;
+    };
+    let (width, height) = if placement.rotated {
+        (piece.original_height, piece.original_width)
+    } else {
+        (piece.original_width, piece.original_height)
+    };
+    (placement.row..placement.row + height).contains(&row) && (placement.col..placement.col + width).contains(&col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Generator;
+    use crate::piece::expand_inventory;
+    use crate::solver::Solver;
+    use crate::types::{Color, Difficulty, Piece, SolveResult};
+    use crate::VOID_CELL;
+
+    /// 变量取值是否满足所有子句
+    fn satisfies(clauses: &[Vec<i64>], chosen: &[i64]) -> bool {
+        clauses.iter().all(|clause| clause.iter().any(|&lit| chosen.contains(&lit.abs()) == (lit > 0)))
+    }
+
+    #[test]
+    fn test_model_round_trips_unique_solution() {
+        let level = Generator::with_seed(4).generate(Difficulty::Medium).unwrap();
+        let SolveResult::UniqueSolution(solution) = Solver::new(2).solve(&level) else {
+            panic!("Generated level should be unique");
+        };
+
+        let mut model = PlacementModel::new(&level);
+        let vars: Vec<i64> = solution
+            .placements
+            .iter()
+            .map(|p| model.placements.iter().position(|q| q == p).unwrap() as i64 + 1)
+            .collect();
+        assert!(satisfies(&model.clauses(), &vars));
+
+        let output = format!("s SATISFIABLE\nv {} 0\n", vars.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" "));
+        let imported = model.solution_from_dimacs(&level, &output).unwrap();
+        assert_eq!(imported.board, solution.board);
+        let values: String = vars.iter().map(|v| format!("{:>6} x{:<6} 1 0\n", 0, v)).collect();
+        assert_eq!(model.solution_from_values(&level, &values).unwrap().board, solution.board);
+
+        let cnf = model.to_dimacs();
+        assert!(cnf.contains(&format!("p cnf {} ", model.variable_count())));
+        assert!(model.to_lp().contains("Binary"));
+        assert!(model.to_mps().ends_with("ENDATA\n"));
+
+        model.exclude(&solution).unwrap();
+        assert!(!satisfies(&model.clauses(), &vars));
+        assert_eq!(model.solution_from_dimacs(&level, "s UNSATISFIABLE\n").unwrap_err(), ModelError::Unsatisfiable);
+        assert!(matches!(model.solution_from_assignment(&level, &vars[1..]), Err(ModelError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_model_count_matches_solver() {
+        // 2×3区域，两个同种多米诺加一个不同颜色的多米诺
+        let domino = Piece::new(1, 1, 2, Color::Black2);
+        let mut pieces = expand_inventory(&[(domino, 2)]);
+        pieces.push(Piece::new(3, 1, 2, Color::Blue1));
+        let mut level = GameState::new(pieces);
+        for idx in 0..crate::TOTAL_CELLS {
+            if idx / BOARD_SIZE >= 2 || idx % BOARD_SIZE >= 3 {
+                level.board.cells[idx] = VOID_CELL;
+            }
+        }
+
+        let models = count_models(&PlacementModel::new(&level));
+        assert_eq!(models, Solver::new(100).count_solutions(&level));
+        // 3种多米诺铺法 × 蓝色方块的3个位置
+        assert_eq!(models, 9);
+    }
+
+    /// 暴力枚举满足所有子句的变量取值数
+    fn count_models(model: &PlacementModel) -> usize {
+        let clauses = model.clauses();
+        let n = model.variable_count();
+        (0u32..1 << n)
+            .filter(|mask| {
+                let chosen: Vec<i64> = (0..n).filter(|i| mask & (1 << i) != 0).map(|i| i as i64 + 1).collect();
+                satisfies(&clauses, &chosen)
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_surplus_twins_are_used_in_order() {
+        // 1×3长条，两个同种1×1加一个1×2：只用第二个1×1的取值不能算作新解
+        let single = Piece::new(1, 1, 1, Color::Black1);
+        let mut pieces = expand_inventory(&[(single, 2)]);
+        pieces.push(Piece::new(3, 1, 2, Color::Black2));
+        let mut level = GameState::new(pieces);
+        for idx in 0..crate::TOTAL_CELLS {
+            if idx >= 3 {
+                level.board.cells[idx] = VOID_CELL;
+            }
+        }
+
+        let model = PlacementModel::new(&level);
+        assert_eq!(count_models(&model), Solver::new(100).count_solutions(&level));
+        assert_eq!(count_models(&model), 2);
+        assert!(model.to_lp().contains(" - x"));
+        assert!(model.to_mps().contains(" -1\n"));
+    }
+
+    #[test]
+    fn test_exclude_empty_solution_makes_model_unsatisfiable() {
+        let mut level = GameState::new(Vec::new());
+        level.board.cells = [VOID_CELL; crate::TOTAL_CELLS];
+        let SolveResult::UniqueSolution(solution) = Solver::new(2).solve(&level) else {
+            panic!("Full board has exactly the empty solution");
+        };
+
+        let mut model = PlacementModel::new(&level);
+        assert_eq!(count_models(&model), 1);
+        model.exclude(&solution).unwrap();
+        assert_eq!(count_models(&model), 0);
+        assert!(model.to_lp().contains("exclude_0: 0 x1 <= -1"));
+    }
+}
@@ -0,0 +1,300 @@
+//! 关卡包模块 - 有序关卡集合、解锁规则和最佳成绩
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use crate::generator::Generator;
+use crate::replay::{count_legal_positions, human_like_order};
+use crate::solver::Solver;
+use crate::types::{Board, Difficulty, GameState, Piece, SolveResult};
+
+/// 关卡包元数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackMetadata {
+    /// 名称
+    pub name: String,
+    /// 描述
+    pub description: String,
+    /// 作者
+    pub author: String,
+    /// 格式版本
+    pub version: u32,
+}
+
+/// 关卡解锁规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnlockRule {
+    /// 始终可玩
+    Always,
+    /// 完成上一关后解锁
+    AfterPrevious,
+    /// 完成指定数量的关卡后解锁
+    AfterSolvedCount(usize),
+}
+
+/// 关卡包中的一关
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackLevel {
+    /// 关卡名称
+    pub name: String,
+    /// 关卡内容
+    pub level: GameState,
+    /// 难度标签
+    pub difficulty: Difficulty,
+    /// 难度分数（越大越难）
+    pub score: u32,
+    /// 解锁规则
+    pub unlock: UnlockRule,
+    /// 最佳用时（毫秒），未完成为None
+    pub best_time_ms: Option<u64>,
+}
+
+/// 关卡概要（用于列表展示，不含棋盘数据）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackLevelSummary {
+    /// 关卡序号
+    pub index: usize,
+    /// 关卡名称
+    pub name: String,
+    /// 难度标签
+    pub difficulty: Difficulty,
+    /// 难度分数
+    pub score: u32,
+    /// 是否已解锁
+    pub unlocked: bool,
+    /// 最佳用时（毫秒）
+    pub best_time_ms: Option<u64>,
+}
+
+/// 关卡包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelPack {
+    /// 元数据
+    pub metadata: PackMetadata,
+    /// 按顺序排列的关卡
+    pub levels: Vec<PackLevel>,
+}
+
+impl LevelPack {
+    /// 创建空关卡包
+    pub fn new(name: &str) -> Self {
+        Self {
+            metadata: PackMetadata {
+                name: name.to_string(),
+                description: String::new(),
+                author: String::new(),
+                version: 1,
+            },
+            levels: Vec::new(),
+        }
+    }
+
+    /// 追加关卡（第一关始终可玩，之后默认完成上一关解锁）
+    pub fn push(&mut self, name: &str, level: GameState, difficulty: Difficulty, score: u32) {
+        let unlock = if self.levels.is_empty() {
+            UnlockRule::Always
+        } else {
+            UnlockRule::AfterPrevious
+        };
+
+        self.levels.push(PackLevel {
+            name: name.to_string(),
+            level,
+            difficulty,
+            score,
+            unlock,
+            best_time_ms: None,
+        });
+    }
+
+    /// 已完成的关卡数
+    pub fn solved_count(&self) -> usize {
+        self.levels.iter().filter(|l| l.best_time_ms.is_some()).count()
+    }
+
+    /// 指定关卡是否已解锁
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        let level = match self.levels.get(index) {
+            Some(level) => level,
+            None => return false,
+        };
+
+        match level.unlock {
+            UnlockRule::Always => true,
+            UnlockRule::AfterPrevious => {
+                index == 0 || self.levels[index - 1].best_time_ms.is_some()
+            }
+            UnlockRule::AfterSolvedCount(count) => self.solved_count() >= count,
+        }
+    }
+
+    /// 记录完成一关，返回是否刷新了最佳用时
+    pub fn record_completion(&mut self, index: usize, time_ms: u64) -> bool {
+        match self.levels.get_mut(index) {
+            Some(level) if level.best_time_ms.is_none_or(|best| time_ms < best) => {
+                level.best_time_ms = Some(time_ms);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 第一个已解锁但未完成的关卡
+    pub fn next_unsolved(&self) -> Option<usize> {
+        (0..self.levels.len()).find(|&i| self.is_unlocked(i) && self.levels[i].best_time_ms.is_none())
+    }
+
+    /// 关卡列表概要
+    pub fn summaries(&self) -> Vec<PackLevelSummary> {
+        self.levels
+            .iter()
+            .enumerate()
+            .map(|(index, level)| PackLevelSummary {
+                index,
+                name: level.name.clone(),
+                difficulty: level.difficulty,
+                score: level.score,
+                unlocked: self.is_unlocked(index),
+                best_time_ms: level.best_time_ms,
+            })
+            .collect()
+    }
+
+    /// 序列化为JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// 从JSON加载
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// 计算关卡的难度分数
+///
+/// 按"人类思路"顺序放置唯一解中的方块，累计每一步被放置方块的候选位置数（减1）。
+/// 每一步都是唯一选择时分数为0；无解时返回None。
+pub fn difficulty_score(level: &GameState) -> Option<u32> {
+    let solution = match Solver::new(1).solve(level) {
+        SolveResult::UniqueSolution(solution) => solution,
+        SolveResult::MultipleSolutions(mut solutions) => solutions.pop()?,
+        SolveResult::NoSolution => return None,
+    };
+
+    let mut board = level.board.clone();
+    let mut score = 0;
+    for placement in human_like_order(level, &solution.placements) {
+        let mut piece: Piece = level.pieces.iter().find(|p| p.id == placement.piece_id)?.clone();
+        piece.reset();
+        score += count_legal_positions(&board, &piece).saturating_sub(1) as u32;
+
+        if placement.rotated {
+            piece.rotate();
+        }
+        board.place(&piece, placement.row, placement.col);
+    }
+
+    Some(score)
+}
+
+impl Generator {
+    /// 生成包含 `count` 个关卡的关卡包
+    ///
+    /// 关卡按难度分数严格递增排列，且任意两关的障碍布局不属于同一对称类。
+    /// 候选关卡不足时返回None。
+    pub fn generate_pack(&self, name: &str, count: usize) -> Option<LevelPack> {
+        let mut seen: HashSet<Board> = HashSet::new();
+        let mut candidates: Vec<(u32, Difficulty, GameState)> = Vec::new();
+        let mut attempts = 0;
+
+        // 先收集足够多不同分数的候选关卡
+        while distinct_scores(&candidates) < count && attempts < count * 10 {
+            attempts += 1;
+            let difficulty = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard][attempts % 3];
+            let level = match self.generate(difficulty) {
+                Some(level) => level,
+                None => continue,
+            };
+            if !seen.insert(level.board.canonical()) {
+                continue;
+            }
+            if let Some(score) = difficulty_score(&level) {
+                candidates.push((score, difficulty, level));
+            }
+        }
+
+        // 每个分数保留一个关卡，再均匀挑选 count 个
+        candidates.sort_by_key(|(score, _, _)| *score);
+        candidates.dedup_by_key(|(score, _, _)| *score);
+        if candidates.len() < count {
+            return None;
+        }
+
+        let mut pack = LevelPack::new(name);
+        for i in 0..count {
+            let pick = if count > 1 { i * (candidates.len() - 1) / (count - 1) } else { 0 };
+            // 难度标签沿用生成时的难度（它决定了障碍配置）
+            let (score, difficulty, level) = candidates[pick].clone();
+            pack.push(&format!("{} #{}", name, i + 1), level, difficulty, score);
+        }
+
+        Some(pack)
+    }
+}
+
+fn distinct_scores(candidates: &[(u32, Difficulty, GameState)]) -> usize {
+    candidates.iter().map(|(score, _, _)| *score).collect::<HashSet<_>>().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_and_best_times() {
+        let generator = Generator::new();
+        let level = generator.generate(Difficulty::Easy).expect("Should generate a level");
+
+        let mut pack = LevelPack::new("test");
+        pack.push("1", level.clone(), Difficulty::Easy, 1);
+        pack.push("2", level.clone(), Difficulty::Easy, 2);
+        pack.push("3", level, Difficulty::Hard, 3);
+        pack.levels[2].unlock = UnlockRule::AfterSolvedCount(2);
+
+        assert!(pack.is_unlocked(0));
+        assert!(!pack.is_unlocked(1));
+        assert!(!pack.is_unlocked(2));
+        assert_eq!(pack.next_unsolved(), Some(0));
+
+        assert!(pack.record_completion(0, 5000));
+        assert!(!pack.record_completion(0, 6000));
+        assert!(pack.record_completion(0, 4000));
+        assert_eq!(pack.levels[0].best_time_ms, Some(4000));
+        assert!(pack.is_unlocked(1));
+        assert!(!pack.is_unlocked(2));
+
+        pack.record_completion(1, 7000);
+        assert!(pack.is_unlocked(2));
+        assert_eq!(pack.next_unsolved(), Some(2));
+
+        let parsed = LevelPack::from_json(&pack.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.summaries(), pack.summaries());
+    }
+
+    #[test]
+    fn test_generate_pack() {
+        let generator = Generator::with_seed(1);
+        let pack = generator.generate_pack("campaign", 3).expect("Seeded pack should generate");
+
+        assert_eq!(pack.levels.len(), 3);
+        for entry in &pack.levels {
+            // 标签与生成时的障碍配置一致
+            assert_eq!(entry.level.obstacle_positions.len(), entry.difficulty.obstacle_profile().count);
+        }
+        for pair in pack.levels.windows(2) {
+            assert!(pair[0].score < pair[1].score);
+            assert_ne!(pair[0].level.board.canonical(), pair[1].level.board.canonical());
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! 棋盘文本解析模块 - 把 `Board::print` 风格的文本图解析回棋盘，方便编写测试和报告问题
+//!
+//! 支持的格子字符：`■`/`#` 障碍，`×`/`x` 空洞，`·`/`.`/`0` 空格，`1`-`9`/`A`-`Z` 方块ID。
+//! 边框字符（`┌─│+-|` 等）和空白会被忽略，只含边框的行会被跳过。
+//!
+//! 往返保证：对 `Display` 输出的文本 `t = board.to_string()`，`t.parse::<Board>()?.to_string() == t`；
+//! 其他写法（`#` 障碍、ASCII边框、多余空白等）能解析，但重新输出的是 `Display` 的标准写法。
+//! 对任意棋盘，解析回来的方块格完全相同，障碍格位置相同。障碍ID由铺法推断，
+//! 相邻障碍有多种铺法时可能与原棋盘不同，但关卡是等价的（障碍方块集合和托盘都一样）。
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::generator::state_from_obstacle_board;
+use crate::piece::get_standard_pieces;
+use crate::types::{Board, GameState, Piece};
+use crate::{BOARD_SIZE, TOTAL_PIECES, VOID_CELL};
+
+/// 解析错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBoardError {
+    /// 行数不对
+    WrongRowCount(usize),
+    /// 某一行的格子数不对
+    WrongRowLength { row: usize, len: usize },
+    /// 无法识别的字符
+    UnknownSymbol { row: usize, col: usize, symbol: char },
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBoardError::WrongRowCount(n) => write!(f, "Expected {} rows, found {}", BOARD_SIZE, n),
+            ParseBoardError::WrongRowLength { row, len } => {
+                write!(f, "Row {} has {} cells, expected {}", row, len, BOARD_SIZE)
+            }
+            ParseBoardError::UnknownSymbol { row, col, symbol } => {
+                write!(f, "Unknown symbol '{}' at ({}, {})", symbol, row, col)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseBoardError {}
+
+/// 用文本图创建棋盘，解析失败时panic（用于测试和固定数据）
+///
+/// ```
+/// use logic_core::board;
+///
+/// let board = board!(
+///     "# # # . . . . ."
+///     ". . . . . . . ."
+///     ". . . . . . . ."
+///     ". . . . . . . ."
+///     ". . . . . . . ."
+///     ". . . . . . . ."
+///     ". . . . . . # #"
+///     ". . . . . . . #"
+/// );
+/// assert_eq!(board.get(0, 0), -3);
+/// ```
+#[macro_export]
+macro_rules! board {
+    ($($line:literal)+) => {
+        concat!($($line, "\n"),+)
+            .parse::<$crate::Board>()
+            .expect("invalid board diagram")
+    };
+}
+
+/// 是否是边框字符
+fn is_border(c: char) -> bool {
+    matches!(c, '|' | '+' | '-') || ('\u{2500}'..='\u{257f}').contains(&c)
+}
+
+/// 格子字符对应的值，障碍先记为-1，稍后推断具体ID
+fn symbol_value(c: char) -> Option<i8> {
+    match c {
+        '■' | '#' => Some(-1),
+        '×' | 'x' => Some(VOID_CELL),
+        '·' | '.' | '0' => Some(0),
+        '1'..='9' => Some(c as i8 - b'0' as i8),
+        'A'..='Z' => Some(c as i8 - b'A' as i8 + 10),
+        _ => None,
+    }
+}
+
+impl FromStr for Board {
+    type Err = ParseBoardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut board = Board::new();
+        let mut row = 0;
+
+        for line in text.lines() {
+            let symbols: Vec<char> = line.chars().filter(|c| !c.is_whitespace() && !is_border(*c)).collect();
+            if symbols.is_empty() {
+                continue;
+            }
+            if row >= BOARD_SIZE {
+                return Err(ParseBoardError::WrongRowCount(row + 1));
+            }
+            if symbols.len() != BOARD_SIZE {
+                return Err(ParseBoardError::WrongRowLength { row, len: symbols.len() });
+            }
+            for (col, &symbol) in symbols.iter().enumerate() {
+                let value = symbol_value(symbol).ok_or(ParseBoardError::UnknownSymbol { row, col, symbol })?;
+                board.set(row, col, value);
+            }
+            row += 1;
+        }
+
+        if row != BOARD_SIZE {
+            return Err(ParseBoardError::WrongRowCount(row));
+        }
+
+        assign_obstacle_ids(&mut board);
+        Ok(board)
+    }
+}
+
+impl FromStr for GameState {
+    type Err = ParseBoardError;
+
+    /// 解析为关卡：障碍方块从托盘中移除，棋盘上已有的方块标记为已使用
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let board: Board = text.parse()?;
+
+        let mut obstacles = board.clone();
+        for cell in obstacles.cells_mut().iter_mut() {
+            if *cell > 0 {
+                *cell = 0;
+            }
+        }
+
+        let mut state = state_from_obstacle_board(obstacles);
+        for (idx, piece) in state.pieces.iter().enumerate() {
+            state.used_pieces[idx] = board.cells().contains(&(piece.id as i8));
+        }
+        state.board = board;
+        Ok(state)
+    }
+}
+
+/// 推断障碍ID
+///
+/// 文本中所有障碍都是同一个字符，这里用标准方块（每个最多一次，ID小的优先）
+/// 恰好铺满所有障碍格，按阅读顺序取第一种铺法。铺不满时（非标准障碍）所有障碍都记为-1。
+fn assign_obstacle_ids(board: &mut Board) {
+    if !board.cells().contains(&-1) {
+        return;
+    }
+
+    let pieces = get_standard_pieces();
+    let mut tiled = board.clone();
+    if tile_obstacles(&mut tiled, &pieces, &mut [false; TOTAL_PIECES]) {
+        *board = tiled;
+    }
+}
+
+/// 回溯铺障碍：待定的障碍格为-1，已确定的为 -(id+TOTAL_PIECES) 占位，完成后换回 -id
+fn tile_obstacles(board: &mut Board, pieces: &[Piece], used: &mut [bool; TOTAL_PIECES]) -> bool {
+    let pending = board.cells().iter().position(|&c| c == -1);
+    let Some(idx) = pending else {
+        for cell in board.cells_mut().iter_mut() {
+            if *cell < -(TOTAL_PIECES as i8) {
+                *cell += TOTAL_PIECES as i8;
+            }
+        }
+        return true;
+    };
+    let (row, col) = (idx / BOARD_SIZE, idx % BOARD_SIZE);
+
+    for (i, piece) in pieces.iter().enumerate() {
+        if used[i] {
+            continue;
+        }
+        let mut piece = piece.clone();
+        let orientations = if piece.width == piece.height { 1 } else { 2 };
+        for rotation in 0..orientations {
+            if rotation == 1 {
+                piece.rotate();
+            }
+            // 待定格是阅读顺序的第一个，所以它一定是方块的左上角
+            let cells: Vec<(usize, usize)> = (row..row + piece.height)
+                .flat_map(|r| (col..col + piece.width).map(move |c| (r, c)))
+                .collect();
+            if !cells.iter().all(|&(r, c)| r < BOARD_SIZE && c < BOARD_SIZE && board.get(r, c) == -1) {
+                continue;
+            }
+
+            let marker = -((piece.id as usize + TOTAL_PIECES) as i8);
+            for &(r, c) in &cells {
+                board.set(r, c, marker);
+            }
+            used[i] = true;
+            if tile_obstacles(board, pieces, used) {
+                return true;
+            }
+            used[i] = false;
+            for &(r, c) in &cells {
+                board.set(r, c, -1);
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use crate::generator::Generator;
+    use crate::render::RenderOptions;
+    use crate::solver::Solver;
+    use crate::types::{Difficulty, SolveResult};
+
+    /// 方块格完全相同，障碍格位置相同
+    fn equivalent(a: &Board, b: &Board) -> bool {
+        a.cells().iter().zip(b.cells()).all(|(&x, &y)| if x < 0 { y < 0 } else { x == y })
+    }
+
+    #[test]
+    fn test_round_trip_all_render_styles() {
+        let generator = Generator::with_seed(4);
+        let outlined = RenderOptions { outlines: true, ..RenderOptions::default() };
+        for _ in 0..5 {
+            let level = generator.generate(Difficulty::Medium).unwrap();
+            let SolveResult::UniqueSolution(solution) = Solver::new(2).solve(&level) else {
+                panic!("Generated level should be unique");
+            };
+
+            for board in [&level.board, &solution.board] {
+                for options in [RenderOptions::default(), RenderOptions::ascii()] {
+                    let text = board.render(&options);
+                    let parsed: Board = text.parse().unwrap();
+                    assert_eq!(parsed.render(&options), text);
+                    assert!(equivalent(&parsed, board));
+                }
+                assert!(equivalent(&board.render(&outlined).parse().unwrap(), board));
+            }
+        }
+    }
+
+    #[test]
+    fn test_obstacle_ids_inferred() {
+        let board = board!(
+            "■ · · · · · · ·"
+            "■ · · · · · · ·"
+            "· · · · · · · ·"
+            "· · · · ■ ■ ■ ·"
+            "· · · · · · · ·"
+            "· · · · · · · ·"
+            "· · · · · · · ·"
+            "· · · · · · · ■"
+        );
+        assert_eq!((board.get(0, 0), board.get(1, 0)), (-2, -2));
+        assert_eq!(board.get(3, 5), -3);
+        assert_eq!(board.get(7, 7), -1);
+    }
+
+    #[test]
+    fn test_parse_game_state() {
+        let state: GameState = "\
+            ■ ■ · · · · · ·
+            · · · · · · · ·
+            · · · · · · · ·
+            · · · · · · · ■
+            · · · · · · · ■
+            · · · · · · · ■
+            6 6 · · · · · ·
+            6 6 · · · · · ■"
+            .parse()
+            .unwrap();
+
+        assert_eq!(state.board.get(0, 0), -2);
+        assert_eq!(state.board.get(3, 7), -3);
+        assert_eq!(state.board.get(7, 7), -1);
+        assert_eq!(state.obstacle_positions.len(), 3);
+        assert_eq!(state.pieces.len(), 8);
+        assert!(state.used_pieces[state.pieces.iter().position(|p| p.id == 6).unwrap()]);
+        assert_eq!(state.used_pieces.iter().filter(|&&u| u).count(), 1);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("# . .".parse::<Board>(), Err(ParseBoardError::WrongRowLength { row: 0, len: 3 }));
+        assert_eq!(". . . . . . . .\n".parse::<Board>(), Err(ParseBoardError::WrongRowCount(1)));
+
+        let text = ". . . . . . . .\n".repeat(7) + ". . . ? . . . .\n";
+        assert_eq!(
+            text.parse::<Board>(),
+            Err(ParseBoardError::UnknownSymbol { row: 7, col: 3, symbol: '?' })
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_text_round_trip(board in crate::testing::board()) {
+            for options in [RenderOptions::default(), RenderOptions::ascii()] {
+                let text = board.render(&options);
+                let parsed: Board = text.parse().unwrap();
+                prop_assert!(equivalent(&parsed, &board));
+                prop_assert_eq!(parsed.render(&options), text);
+            }
+        }
+
+        #[test]
+        fn prop_json_round_trip(board in crate::testing::board()) {
+            let json = serde_json::to_string(&board).unwrap();
+            prop_assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+        }
+
+        #[test]
+        fn prop_parse_never_panics(text in "[#.·■0-9A-Z │|+\n-]{0,200}") {
+            let _ = text.parse::<Board>();
+            let _ = text.parse::<GameState>();
+        }
+    }
+}
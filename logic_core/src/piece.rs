@@ -1,6 +1,6 @@
 //! 方块定义模块
 
-use crate::types::{Color, Piece};
+use crate::types::{Board, Color, Piece};
 
 /// 获取标准的11个方块（每个方块有独立颜色）
 pub fn get_standard_pieces() -> Vec<Piece> {
@@ -28,9 +28,24 @@ pub fn get_standard_pieces() -> Vec<Piece> {
     ]
 }
 
+/// 按 (方块种类, 数量) 展开成方块列表，ID从1开始依次编号
+///
+/// 例如五个1×2多米诺：`expand_inventory(&[(Piece::new(1, 1, 2, Color::Black2), 5)])`。
+/// 棋盘格子用i8存ID，所以方块总数不能超过127。
+pub fn expand_inventory(kinds: &[(Piece, usize)]) -> Vec<Piece> {
+    let pieces: Vec<Piece> = kinds
+        .iter()
+        .flat_map(|(piece, count)| std::iter::repeat_n(piece, *count))
+        .zip(1..)
+        .map(|(piece, id)| Piece { id, ..piece.clone() })
+        .collect();
+    debug_assert!(pieces.len() <= i8::MAX as usize);
+    pieces
+}
+
 /// 根据ID获取方块
 pub fn get_piece_by_id(id: u8) -> Option<Piece> {
-    if id >= 1 && id <= 11 {
+    if (1..=11).contains(&id) {
         Some(get_standard_pieces()[(id - 1) as usize].clone())
     } else {
         None
@@ -40,9 +55,13 @@ pub fn get_piece_by_id(id: u8) -> Option<Piece> {
 /// 验证所有方块的总面积是否正确
 /// 11个方块总面积 = 1 + 2 + 3 + 4 + 5 + 4 + 6 + 8 + 10 + 9 + 12 = 64
 pub fn verify_total_area() -> bool {
-    let pieces = get_standard_pieces();
+    verify_area(&get_standard_pieces(), &Board::new())
+}
+
+/// 验证方块总面积是否恰好等于棋盘上的空格数（障碍和空洞不计）
+pub fn verify_area(pieces: &[Piece], board: &Board) -> bool {
     let total_area: usize = pieces.iter().map(|p| p.area()).sum();
-    total_area == 64
+    total_area == board.count_empty()
 }
 
 #[cfg(test)]
@@ -69,6 +88,11 @@ mod tests {
         let pieces = get_standard_pieces();
         let total: usize = pieces.iter().map(|p| p.area()).sum();
         assert_eq!(total, 64, "Total area should be 64 (8x8 board)");
+
+        // 挖掉一格后去掉1×1正好填满
+        let board = Board::new().with_voids(&[(0, 0)]);
+        assert!(verify_area(&pieces[1..], &board));
+        assert!(!verify_area(&pieces, &board));
     }
 
     #[test]
@@ -109,6 +133,12 @@ mod tests {
         assert_eq!(pieces[4].color.family(), "蓝色系");
     }
 
+    #[test]
+    fn test_color_rgb() {
+        assert_eq!(Color::Black1.rgb(), (0x1a, 0x25, 0x2f));
+        assert_eq!(Color::Yellow2.rgb(), (0xf3, 0x9c, 0x12));
+    }
+
     #[test]
     fn test_piece_rotation() {
         let mut piece = Piece::new(1, 2, 3, Color::Black1);
@@ -127,6 +157,24 @@ mod tests {
         assert!(!piece.rotated);
     }
 
+    #[test]
+    fn test_expand_inventory() {
+        let domino = Piece::new(1, 1, 2, Color::Black2);
+        let tromino = Piece::new(1, 1, 3, Color::Black3);
+        let pieces = expand_inventory(&[(domino.clone(), 3), (tromino, 2)]);
+
+        assert_eq!(pieces.iter().map(|p| p.id).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        assert!(pieces[0].same_kind(&pieces[2]) && !pieces[2].same_kind(&pieces[3]));
+
+        let mut state = crate::types::GameState::new(pieces);
+        state.used_pieces[0] = true;
+        let inventory = state.inventory();
+        assert_eq!(inventory.len(), 2);
+        assert_eq!((inventory[0].0.id, inventory[0].1), (2, 2));
+        assert!(inventory[0].0.same_kind(&domino));
+        assert_eq!(inventory[1].1, 2);
+    }
+
     #[test]
     fn test_get_piece_by_id() {
         for id in 1..=11 {
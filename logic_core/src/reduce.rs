@@ -0,0 +1,195 @@
+//! 关卡精简模块 - 从完整解出发去掉尽量多的预置方块，得到给定最少的唯一解关卡
+
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::Generator;
+use crate::types::{GameState, Solution};
+
+/// 精简结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reduction {
+    /// 精简后的关卡（保留的方块作为障碍）
+    pub level: GameState,
+    /// 保留的方块ID
+    pub givens: Vec<u8>,
+    /// 贪心阶段结束时保留的方块数
+    pub greedy_givens: usize,
+    /// 是否已证明没有更少给定的唯一解关卡（检查次数用完时为false）
+    pub provably_minimal: bool,
+    /// 唯一性检查次数
+    pub checks: usize,
+}
+
+impl Generator {
+    /// 从完整解精简出给定最少的关卡
+    ///
+    /// 先按随机顺序逐个尝试去掉方块（仍唯一就去掉），再从解的全部方块中穷举少一个给定的所有组合：
+    /// 给定越多越容易唯一，所以任何少一个的组合都不唯一时结果就是最少的。
+    /// 穷举最多做 `max_checks` 次唯一性检查。
+    pub fn reduce_solution(&self, solution: &Solution, max_checks: usize) -> Reduction {
+        let mut checks = 0;
+        let mut givens: Vec<u8> = solution.placements.iter().map(|p| p.piece_id).collect();
+        givens.shuffle(&mut *self.rng.borrow_mut());
+
+        self.remove_greedily(solution, &mut givens, &mut checks);
+        let greedy_givens = givens.len();
+
+        let mut provably_minimal = false;
+        while !givens.is_empty() {
+            match self.find_smaller_unique(solution, &givens, &mut checks, max_checks) {
+                Some(Some(smaller)) => {
+                    givens = smaller;
+                    self.remove_greedily(solution, &mut givens, &mut checks);
+                }
+                Some(None) => {
+                    provably_minimal = true;
+                    break;
+                }
+                None => break,
+            }
+        }
+        if givens.is_empty() {
+            provably_minimal = true;
+        }
+
+        givens.sort_unstable();
+        Reduction {
+            level: self.create_state_from_solution(solution, &givens),
+            givens,
+            greedy_givens,
+            provably_minimal,
+            checks,
+        }
+    }
+
+    /// 以 `solution` 为答案、`givens` 为障碍的关卡是否唯一
+    fn is_unique_with(&self, solution: &Solution, givens: &[u8], checks: &mut usize) -> bool {
+        *checks += 1;
+        self.solver.count_solutions(&self.create_state_from_solution(solution, givens)) == 1
+    }
+
+    /// 依次尝试去掉每个给定，去掉后仍唯一就保留这次去掉
+    fn remove_greedily(&self, solution: &Solution, givens: &mut Vec<u8>, checks: &mut usize) {
+        let mut i = 0;
+        while i < givens.len() {
+            let removed = givens.remove(i);
+            if !self.is_unique_with(solution, givens, checks) {
+                givens.insert(i, removed);
+                i += 1;
+            }
+        }
+    }
+
+    /// 在解的全部方块中找一个比 `givens` 少一个方块的唯一组合
+    ///
+    /// 只在 `givens` 内部找没有意义：贪心阶段已经证明从中去掉任何一个都不唯一。
+    /// 返回 `Some(Some(组合))` 找到，`Some(None)` 穷举完没有，`None` 检查次数用完。
+    fn find_smaller_unique(
+        &self,
+        solution: &Solution,
+        givens: &[u8],
+        checks: &mut usize,
+        max_checks: usize,
+    ) -> Option<Option<Vec<u8>>> {
+        let all: Vec<u8> = solution.placements.iter().map(|p| p.piece_id).collect();
+        let mut subset = Vec::with_capacity(givens.len() - 1);
+        self.search_subsets(solution, &all, givens.len() - 1, &mut subset, checks, max_checks)
+    }
+
+    /// 从 `remaining` 中补齐 `subset` 到 `size` 个方块的所有组合
+    fn search_subsets(
+        &self,
+        solution: &Solution,
+        remaining: &[u8],
+        size: usize,
+        subset: &mut Vec<u8>,
+        checks: &mut usize,
+        max_checks: usize,
+    ) -> Option<Option<Vec<u8>>> {
+        if subset.len() == size {
+            if *checks >= max_checks {
+                return None;
+            }
+            return Some(self.is_unique_with(solution, subset, checks).then(|| subset.clone()));
+        }
+
+        for i in 0..remaining.len() {
+            // 剩下的方块不够凑满组合
+            if remaining.len() - i < size - subset.len() {
+                break;
+            }
+            subset.push(remaining[i]);
+            let found = self.search_subsets(solution, &remaining[i + 1..], size, subset, checks, max_checks);
+            subset.pop();
+            if !matches!(found, Some(None)) {
+                return found;
+            }
+        }
+        Some(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+    use crate::types::{Difficulty, SolveResult};
+
+    /// 暴力求出最少给定数
+    fn minimum_givens(generator: &Generator, solution: &Solution) -> usize {
+        let ids: Vec<u8> = solution.placements.iter().map(|p| p.piece_id).collect();
+        (0u32..1 << ids.len())
+            .filter(|mask| {
+                let givens: Vec<u8> = (0..ids.len()).filter(|i| mask & (1 << i) != 0).map(|i| ids[i]).collect();
+                generator.is_unique_with(solution, &givens, &mut 0)
+            })
+            .map(|mask| mask.count_ones() as usize)
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reduce_solution_matches_brute_force_minimum() {
+        for seed in [0, 4, 9] {
+            let generator = Generator::with_seed(seed);
+            let level = generator.generate(Difficulty::Medium).unwrap();
+            let SolveResult::UniqueSolution(mut solution) = Solver::new(2).solve(&level) else {
+                panic!("Generated level should be unique");
+            };
+            solution.placements.extend(level.obstacle_placements());
+
+            let reduction = generator.reduce_solution(&solution, usize::MAX);
+            assert!(reduction.provably_minimal);
+            assert_eq!(reduction.givens.len(), minimum_givens(&generator, &solution), "seed {}", seed);
+            assert_eq!(Solver::new(2).count_solutions(&reduction.level), 1);
+        }
+    }
+
+    #[test]
+    fn test_obstacles_extend_level_solution() {
+        let generator = Generator::with_seed(4);
+        let level = generator.generate(Difficulty::Easy).unwrap();
+        let SolveResult::UniqueSolution(mut solution) = Solver::new(2).solve(&level) else {
+            panic!("Generated level should be unique");
+        };
+
+        // 关卡的障碍加上解就是完整解，按原障碍重建得到同一个关卡
+        let obstacle_ids: Vec<u8> = level.obstacle_positions.iter().map(|&(_, _, id)| id).collect();
+        solution.placements.extend(level.obstacle_placements());
+        assert_eq!(generator.create_state_from_solution(&solution, &obstacle_ids).board, level.board);
+        assert!(generator.reduce_solution(&solution, usize::MAX).givens.len() <= obstacle_ids.len());
+    }
+
+    #[test]
+    fn test_reduce_solution_without_budget() {
+        let generator = Generator::with_seed(8);
+        let solution = generator.generate_complete_solution().unwrap();
+        let reduction = generator.reduce_solution(&solution, 0);
+
+        // 贪心阶段总会完成，但没有预算证明最少
+        assert!(!reduction.provably_minimal);
+        assert_eq!(reduction.givens.len(), reduction.greedy_givens);
+        assert_eq!(Solver::new(2).count_solutions(&reduction.level), 1);
+    }
+}
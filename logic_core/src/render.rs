@@ -0,0 +1,328 @@
+//! 棋盘文本渲染模块 - 用于日志、命令行和快照测试
+
+use std::fmt;
+
+use crate::piece::get_piece_by_id;
+use crate::types::Board;
+use crate::{BOARD_SIZE, VOID_CELL};
+
+/// 格子字符集
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderCharset {
+    /// 纯ASCII：`#` 障碍，`.` 空格，方块ID（10以上用字母）
+    Ascii,
+    /// Unicode（与 `Board::print` 一致）：`■` 障碍，`·` 空格，方块ID
+    #[default]
+    Unicode,
+    /// Unicode色块：已占用的格子画成 `█`，适合配合ANSI颜色使用
+    Blocks,
+}
+
+/// 渲染选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderOptions {
+    /// 字符集
+    pub charset: RenderCharset,
+    /// 是否使用24位ANSI颜色（颜色取自 `Color::to_css`）
+    pub ansi_color: bool,
+    /// 是否在不同方块之间画边框
+    pub outlines: bool,
+    /// 是否显示行列坐标
+    pub coordinates: bool,
+}
+
+impl RenderOptions {
+    /// 纯ASCII（适合日志和快照测试）
+    pub fn ascii() -> Self {
+        Self {
+            charset: RenderCharset::Ascii,
+            ..Self::default()
+        }
+    }
+
+    /// 彩色色块（适合终端展示）
+    pub fn color_blocks() -> Self {
+        Self {
+            charset: RenderCharset::Blocks,
+            ansi_color: true,
+            outlines: false,
+            coordinates: false,
+        }
+    }
+}
+
+impl Board {
+    /// 把棋盘渲染为文本
+    pub fn render(&self, options: &RenderOptions) -> String {
+        if options.outlines {
+            render_outlined(self, options)
+        } else {
+            render_plain(self, options)
+        }
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(&RenderOptions::default()))
+    }
+}
+
+/// 方块ID对应的单个字符（10以上用字母）
+pub(crate) fn id_char(id: u8) -> char {
+    match id {
+        0..=9 => (b'0' + id) as char,
+        10..=35 => (b'A' + id - 10) as char,
+        _ => '?',
+    }
+}
+
+/// 格子的显示字符
+fn cell_symbol(cell: i8, charset: RenderCharset) -> char {
+    match (charset, cell) {
+        (RenderCharset::Ascii, VOID_CELL) => 'x',
+        (RenderCharset::Unicode, VOID_CELL) => '×',
+        (RenderCharset::Blocks, VOID_CELL) => ' ',
+        (RenderCharset::Ascii, 0) => '.',
+        (RenderCharset::Ascii, c) if c < 0 => '#',
+        (RenderCharset::Unicode, 0) => '·',
+        (RenderCharset::Unicode, c) if c < 0 => '■',
+        (RenderCharset::Blocks, 0) => '·',
+        (RenderCharset::Blocks, _) => '█',
+        (_, c) => id_char(c as u8),
+    }
+}
+
+/// 给文本加上格子对应方块颜色的ANSI前景色
+fn paint(text: &str, cell: i8, options: &RenderOptions) -> String {
+    if !options.ansi_color || cell == 0 {
+        return text.to_string();
+    }
+    match get_piece_by_id(cell.unsigned_abs()) {
+        Some(piece) => {
+            let (r, g, b) = piece.color.rgb();
+            format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, text)
+        }
+        None => text.to_string(),
+    }
+}
+
+/// 列坐标行
+fn column_labels(cell_width: usize, separator: usize, indent: usize) -> String {
+    let mut line = " ".repeat(indent);
+    for col in 0..BOARD_SIZE {
+        line.push_str(&" ".repeat(separator));
+        let label = id_char(col as u8).to_string();
+        line.push_str(&format!("{:^width$}", label, width = cell_width));
+    }
+    line.trim_end().to_string() + "\n"
+}
+
+/// 不画方块边框：每格两个字符宽，外面一圈边框
+fn render_plain(board: &Board, options: &RenderOptions) -> String {
+    let ascii = options.charset == RenderCharset::Ascii;
+    let (top, bottom, side, horizontal) = if ascii {
+        (("+", "+"), ("+", "+"), "|", "--")
+    } else {
+        (("┌", "┐"), ("└", "┘"), "│", "──")
+    };
+    let indent = if options.coordinates { 2 } else { 0 };
+    let row_label = |row: usize| if options.coordinates { format!("{} ", id_char(row as u8)) } else { String::new() };
+
+    let mut out = String::new();
+    if options.coordinates {
+        out.push_str(&column_labels(2, 0, indent + 1));
+    }
+    out.push_str(&format!("{}{}{}{}\n", " ".repeat(indent), top.0, horizontal.repeat(BOARD_SIZE), top.1));
+    for row in 0..BOARD_SIZE {
+        out.push_str(&row_label(row));
+        out.push_str(side);
+        for col in 0..BOARD_SIZE {
+            let cell = board.get(row, col);
+            let symbol = cell_symbol(cell, options.charset);
+            let text = if options.charset == RenderCharset::Blocks && cell != 0 {
+                format!("{}{}", symbol, symbol)
+            } else {
+                format!("{} ", symbol)
+            };
+            out.push_str(&paint(&text, cell, options));
+        }
+        out.push_str(side);
+        out.push('\n');
+    }
+    out.push_str(&format!("{}{}{}{}\n", " ".repeat(indent), bottom.0, horizontal.repeat(BOARD_SIZE), bottom.1));
+    out
+}
+
+/// 画方块边框：相邻格子属于不同方块时画分隔线
+fn render_outlined(board: &Board, options: &RenderOptions) -> String {
+    let ascii = options.charset == RenderCharset::Ascii;
+    let cell_at = |row: isize, col: isize| -> Option<i8> {
+        if row < 0 || col < 0 || row >= BOARD_SIZE as isize || col >= BOARD_SIZE as isize {
+            None
+        } else {
+            Some(board.get(row as usize, col as usize))
+        }
+    };
+    // 两个格子之间是否需要分隔线（空格之间也画，方便看清网格）
+    let split = |a: Option<i8>, b: Option<i8>| a != b || a == Some(0);
+
+    let indent = if options.coordinates { 2 } else { 0 };
+    let mut out = String::new();
+    if options.coordinates {
+        out.push_str(&column_labels(3, 1, indent));
+    }
+
+    for line in 0..=BOARD_SIZE * 2 {
+        let row = (line / 2) as isize;
+        if line % 2 == 1 && options.coordinates {
+            out.push_str(&format!("{} ", id_char(row as u8)));
+        } else {
+            out.push_str(&" ".repeat(indent));
+        }
+
+        for col in 0..=BOARD_SIZE as isize {
+            if line % 2 == 0 {
+                // 交叉点：上下左右四条线段
+                let up = split(cell_at(row - 1, col - 1), cell_at(row - 1, col));
+                let down = split(cell_at(row, col - 1), cell_at(row, col));
+                let left = split(cell_at(row - 1, col - 1), cell_at(row, col - 1));
+                let right = split(cell_at(row - 1, col), cell_at(row, col));
+                out.push(junction(up, down, left, right, ascii));
+
+                if col < BOARD_SIZE as isize {
+                    let segment = if right { if ascii { "---" } else { "───" } } else { "   " };
+                    out.push_str(segment);
+                }
+            } else {
+                let left = cell_at(row, col - 1);
+                let here = cell_at(row, col);
+                out.push(if split(left, here) { if ascii { '|' } else { '│' } } else { ' ' });
+
+                if let Some(cell) = here {
+                    let symbol = cell_symbol(cell, options.charset);
+                    let text = if options.charset == RenderCharset::Blocks && cell != 0 {
+                        symbol.to_string().repeat(3)
+                    } else {
+                        format!(" {} ", symbol)
+                    };
+                    out.push_str(&paint(&text, cell, options));
+                }
+            }
+        }
+        let trimmed = out.trim_end_matches(' ').len();
+        out.truncate(trimmed);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// 根据四个方向是否有线段选择交叉点字符
+fn junction(up: bool, down: bool, left: bool, right: bool, ascii: bool) -> char {
+    if ascii {
+        return match (up || down, left || right) {
+            (true, true) => '+',
+            (true, false) => '|',
+            (false, true) => '-',
+            (false, false) => ' ',
+        };
+    }
+
+    match (up, down, left, right) {
+        (false, false, false, false) => ' ',
+        (true, true, false, false) | (true, false, false, false) | (false, true, false, false) => '│',
+        (false, false, true, true) | (false, false, true, false) | (false, false, false, true) => '─',
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::get_standard_pieces;
+
+    fn sample_board() -> Board {
+        let pieces = get_standard_pieces();
+        let mut board = Board::new();
+        board.set(0, 0, -1);
+        board.place(&pieces[10], 0, 1); // 3x4
+        board.place(&pieces[5], 4, 0); // 2x2
+        board
+    }
+
+    #[test]
+    fn test_render_ascii_snapshot() {
+        let expected = "\
++----------------+
+|# B B B . . . . |
+|. B B B . . . . |
+|. B B B . . . . |
+|. B B B . . . . |
+|6 6 . . . . . . |
+|6 6 . . . . . . |
+|. . . . . . . . |
+|. . . . . . . . |
++----------------+
+";
+        assert_eq!(sample_board().render(&RenderOptions::ascii()), expected);
+    }
+
+    #[test]
+    fn test_render_unicode_matches_display() {
+        let board = sample_board();
+        let text = board.render(&RenderOptions::default());
+
+        assert_eq!(text, board.to_string());
+        assert!(text.starts_with("┌────────────────┐\n│■ B B B · · · · │\n"));
+    }
+
+    #[test]
+    fn test_render_outlines_and_coordinates() {
+        let mut board = Board::new();
+        board.place(&get_standard_pieces()[5], 0, 0); // 2x2
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                if board.get(row, col) == 0 {
+                    board.set(row, col, -1);
+                }
+            }
+        }
+
+        let options = RenderOptions {
+            charset: RenderCharset::Ascii,
+            outlines: true,
+            coordinates: true,
+            ..RenderOptions::default()
+        };
+        let text = board.render(&options);
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], "    0   1   2   3   4   5   6   7");
+        assert_eq!(lines[1], "  +-------+-----------------------+");
+        assert_eq!(lines[2], "0 | 6   6 | #   #   #   #   #   # |");
+        assert_eq!(lines[3], "  |       |                       |");
+        assert_eq!(lines[5], "  +-------+                       |");
+        assert_eq!(lines[6], "2 | #   #   #   #   #   #   #   # |");
+        assert_eq!(lines.len(), 2 + BOARD_SIZE * 2);
+        assert_eq!(lines.last(), Some(&"  +-------------------------------+"));
+    }
+
+    #[test]
+    fn test_render_ansi_color() {
+        let options = RenderOptions::color_blocks();
+        let text = sample_board().render(&options);
+
+        // 3x4方块为浅灰色 #95a5a6
+        assert!(text.contains("\x1b[38;2;149;165;166m██\x1b[0m"));
+        assert!(text.contains("·"));
+    }
+}
@@ -0,0 +1,275 @@
+//! 回放模块 - 把解或操作历史转换为带时间戳的步骤序列
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use crate::session::{Move, MoveKind};
+use crate::types::{Board, GameState, ParseNameError, Piece, Placement, Solution};
+use crate::BOARD_SIZE;
+
+/// 解的回放顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayOrder {
+    /// 求解器找到解时的顺序（DFS顺序）
+    Solver,
+    /// 按左上角位置逐行排列
+    Reading,
+    /// 模拟人类思路：每一步都放置当前可放位置最少的方块
+    HumanLike,
+}
+
+impl FromStr for ReplayOrder {
+    type Err = ParseNameError;
+
+    /// 解析 `solver` / `reading` / `human`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "solver" => Ok(ReplayOrder::Solver),
+            "reading" => Ok(ReplayOrder::Reading),
+            "human" => Ok(ReplayOrder::HumanLike),
+            _ => Err(ParseNameError { kind: "replay order", name: s.to_string() }),
+        }
+    }
+}
+
+/// 回放
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    /// 初始关卡
+    pub level: GameState,
+    /// 按时间排序的步骤
+    pub steps: Vec<Move>,
+    /// 总时长（毫秒）
+    pub duration_ms: u64,
+}
+
+impl Replay {
+    /// 从解生成回放，每步间隔 `step_ms` 毫秒
+    pub fn from_solution(level: &GameState, solution: &Solution, order: ReplayOrder, step_ms: u64) -> Self {
+        let placements = match order {
+            ReplayOrder::Solver => solution.placements.clone(),
+            ReplayOrder::Reading => {
+                let mut placements = solution.placements.clone();
+                placements.sort_by_key(|p| (p.row, p.col));
+                placements
+            }
+            ReplayOrder::HumanLike => human_like_order(level, &solution.placements),
+        };
+
+        let steps: Vec<Move> = placements
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| Move {
+                kind: MoveKind::Place,
+                piece_id: p.piece_id,
+                row: p.row,
+                col: p.col,
+                rotated: p.rotated,
+                at_ms: (i as u64 + 1) * step_ms,
+            })
+            .collect();
+
+        let duration_ms = steps.last().map_or(0, |m| m.at_ms);
+
+        Self {
+            level: level.clone(),
+            steps,
+            duration_ms,
+        }
+    }
+
+    /// 从会话的操作历史生成回放（保留原始时间戳）
+    pub fn from_history(level: &GameState, history: &[Move]) -> Self {
+        let mut steps = history.to_vec();
+        steps.sort_by_key(|m| m.at_ms);
+        let duration_ms = steps.last().map_or(0, |m| m.at_ms);
+
+        Self {
+            level: level.clone(),
+            steps,
+            duration_ms,
+        }
+    }
+
+    /// 执行前 `step_count` 步后的棋盘
+    pub fn board_at(&self, step_count: usize) -> Board {
+        let mut board = self.level.board.clone();
+
+        for step in self.steps.iter().take(step_count) {
+            let piece = match self.piece_for(step) {
+                Some(piece) => piece,
+                None => continue,
+            };
+            match step.kind {
+                MoveKind::Place => {
+                    if board.can_place(&piece, step.row, step.col) {
+                        board.place(&piece, step.row, step.col);
+                    }
+                }
+                MoveKind::Remove => board.remove(&piece, step.row, step.col),
+                MoveKind::Rotate => {}
+            }
+        }
+
+        board
+    }
+
+    /// 指定时间点的棋盘
+    pub fn board_at_time(&self, at_ms: u64) -> Board {
+        let count = self.steps.iter().take_while(|m| m.at_ms <= at_ms).count();
+        self.board_at(count)
+    }
+
+    /// 序列化为JSON（用于分享回放）
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// 从JSON解析回放
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// 步骤对应朝向的方块
+    fn piece_for(&self, step: &Move) -> Option<Piece> {
+        let mut piece = self.level.pieces.iter().find(|p| p.id == step.piece_id)?.clone();
+        piece.reset();
+        if step.rotated {
+            piece.rotate();
+        }
+        Some(piece)
+    }
+}
+
+/// 按约束程度排序：每一步选择在当前棋盘上合法位置最少的方块，
+/// 相同时优先面积大的方块
+pub(crate) fn human_like_order(level: &GameState, placements: &[Placement]) -> Vec<Placement> {
+    let mut board = level.board.clone();
+    let mut remaining: Vec<(Placement, Piece)> = placements
+        .iter()
+        .filter_map(|p| {
+            let mut piece = level.pieces.iter().find(|piece| piece.id == p.piece_id)?.clone();
+            piece.reset();
+            Some((p.clone(), piece))
+        })
+        .collect();
+
+    let mut ordered = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let best = (0..remaining.len())
+            .min_by_key(|&i| {
+                let piece = &remaining[i].1;
+                (count_legal_positions(&board, piece), std::cmp::Reverse(piece.area()))
+            })
+            .unwrap_or(0);
+
+        let (placement, mut piece) = remaining.swap_remove(best);
+        if placement.rotated {
+            piece.rotate();
+        }
+        board.place(&piece, placement.row, placement.col);
+        ordered.push(placement);
+    }
+
+    ordered
+}
+
+/// 方块在棋盘上的合法位置数（两种朝向）
+pub(crate) fn count_legal_positions(board: &Board, piece: &Piece) -> usize {
+    let mut piece = piece.clone();
+    let orientations = if piece.width == piece.height { 1 } else { 2 };
+    let mut count = 0;
+
+    for _ in 0..orientations {
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                if board.can_place(&piece, row, col) {
+                    count += 1;
+                }
+            }
+        }
+        piece.rotate();
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::get_standard_pieces;
+    use crate::session::Session;
+    use crate::solver::Solver;
+    use crate::types::SolveResult;
+
+    #[test]
+    fn test_parse_replay_order() {
+        assert_eq!("human".parse(), Ok(ReplayOrder::HumanLike));
+        assert_eq!("dfs".parse::<ReplayOrder>().unwrap_err().to_string(), "Invalid replay order: dfs");
+    }
+
+    fn level_and_solution() -> (GameState, Solution) {
+        let mut pieces = get_standard_pieces();
+        let mut board = Board::new();
+        board.set(7, 7, -1);
+        board.set(7, 5, -2);
+        board.set(7, 6, -2);
+        board.set(7, 2, -3);
+        board.set(7, 3, -3);
+        board.set(7, 4, -3);
+        pieces.drain(0..3);
+
+        let mut level = GameState::new(pieces);
+        level.board = board;
+
+        let solution = match Solver::new(1).solve(&level) {
+            SolveResult::UniqueSolution(s) => s,
+            SolveResult::MultipleSolutions(mut s) => s.remove(0),
+            SolveResult::NoSolution => panic!("Level should be solvable"),
+        };
+        (level, solution)
+    }
+
+    #[test]
+    fn test_replay_orders_reach_solution() {
+        let (level, solution) = level_and_solution();
+
+        for order in [ReplayOrder::Solver, ReplayOrder::Reading, ReplayOrder::HumanLike] {
+            let replay = Replay::from_solution(&level, &solution, order, 500);
+            assert_eq!(replay.steps.len(), solution.placements.len());
+            assert_eq!(replay.duration_ms, 500 * solution.placements.len() as u64);
+            assert_eq!(replay.board_at(replay.steps.len()), solution.board);
+            assert_eq!(replay.board_at(0), level.board);
+        }
+    }
+
+    #[test]
+    fn test_replay_from_history() {
+        let (level, solution) = level_and_solution();
+        let mut session = Session::new(level.clone());
+        session.place(6, 0, 0).unwrap();
+        session.remove(6).unwrap();
+        for placement in &solution.placements {
+            if placement.rotated {
+                session.rotate_in_tray(placement.piece_id).unwrap();
+            }
+            session.place(placement.piece_id, placement.row, placement.col).unwrap();
+        }
+
+        let replay = Replay::from_history(&level, session.history());
+        assert_eq!(replay.steps.len(), session.history().len());
+        assert_eq!(replay.board_at(1).get(0, 0), 6);
+        assert_eq!(replay.board_at(2).get(0, 0), 0);
+        assert_eq!(replay.board_at(replay.steps.len()), solution.board);
+    }
+
+    #[test]
+    fn test_replay_json_roundtrip() {
+        let (level, solution) = level_and_solution();
+        let replay = Replay::from_solution(&level, &solution, ReplayOrder::HumanLike, 300);
+
+        let parsed = Replay::from_json(&replay.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.steps, replay.steps);
+        assert_eq!(parsed.board_at_time(parsed.duration_ms), solution.board);
+    }
+}
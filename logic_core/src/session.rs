@@ -0,0 +1,512 @@
+//! 游戏会话模块 - 权威的游戏规则与状态
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use crate::replay::Replay;
+use crate::solver::Solver;
+use crate::types::{Board, GameState, Piece, Placement, SolveResult};
+use crate::verify::{verify_solution, VerificationError, VerifiedSolution};
+use crate::TOTAL_CELLS;
+
+/// 当前时间（毫秒）
+pub(crate) fn now_ms() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now() as u64
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// 会话操作错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionError {
+    /// 关卡中没有这个方块
+    UnknownPiece(u8),
+    /// 方块已经放在棋盘上
+    PieceAlreadyPlaced(u8),
+    /// 方块不在棋盘上
+    PieceNotPlaced(u8),
+    /// 越界、重叠或覆盖障碍
+    InvalidPlacement { piece_id: u8, row: usize, col: usize },
+    /// 格子上没有可移除的方块（空格或障碍）
+    NoPieceAt { row: usize, col: usize },
+    /// 关卡已经完成，不再接受操作
+    AlreadySolved,
+    /// 方块是固定提示，不能移动
+    LockedPiece(u8),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::UnknownPiece(id) => write!(f, "Unknown piece ID: {}", id),
+            SessionError::PieceAlreadyPlaced(id) => write!(f, "Piece {} is already placed", id),
+            SessionError::PieceNotPlaced(id) => write!(f, "Piece {} is not on the board", id),
+            SessionError::InvalidPlacement { piece_id, row, col } => {
+                write!(f, "Piece {} cannot be placed at ({}, {})", piece_id, row, col)
+            }
+            SessionError::NoPieceAt { row, col } => write!(f, "No piece at ({}, {})", row, col),
+            SessionError::AlreadySolved => write!(f, "Level is already solved"),
+            SessionError::LockedPiece(id) => write!(f, "Piece {} is a fixed clue", id),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+/// 操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveKind {
+    /// 放置方块
+    Place,
+    /// 移除方块
+    Remove,
+    /// 在托盘中旋转方块
+    Rotate,
+}
+
+/// 一次操作记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Move {
+    /// 操作类型
+    pub kind: MoveKind,
+    /// 方块ID
+    pub piece_id: u8,
+    /// 行位置（旋转操作为0）
+    pub row: usize,
+    /// 列位置（旋转操作为0）
+    pub col: usize,
+    /// 操作后方块是否处于旋转状态
+    pub rotated: bool,
+    /// 距离会话开始的毫秒数
+    pub at_ms: u64,
+}
+
+/// 会话进度
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    /// 已放置的方块数
+    pub placed_pieces: usize,
+    /// 需要放置的方块总数
+    pub total_pieces: usize,
+    /// 已填充的格子数（不含障碍）
+    pub filled_cells: usize,
+    /// 需要填充的格子总数（不含障碍）
+    pub total_cells: usize,
+    /// 已用时间（毫秒），完成后停止计时
+    pub elapsed_ms: u64,
+    /// 操作次数
+    pub moves: usize,
+    /// 使用提示次数
+    pub hints_used: u32,
+    /// 是否已完成
+    pub solved: bool,
+}
+
+/// 游戏会话
+///
+/// 持有关卡、当前棋盘、计时器和操作历史，所有放置规则都在这里校验，
+/// 前端只需要转发玩家操作并展示返回的状态。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// 初始关卡（只含障碍和固定提示）
+    level: GameState,
+    /// 当前棋盘
+    board: Board,
+    /// 托盘中的方块（保留玩家选择的朝向）
+    pieces: Vec<Piece>,
+    /// 每个方块当前的放置位置
+    placements: Vec<Option<Placement>>,
+    /// 操作历史
+    history: Vec<Move>,
+    /// 会话开始时间（毫秒）
+    started_at_ms: u64,
+    /// 完成时间（毫秒）
+    finished_at_ms: Option<u64>,
+    /// 使用提示次数
+    hints_used: u32,
+}
+
+impl Session {
+    /// 从关卡创建新会话
+    ///
+    /// 棋盘上的正数格子（已放置的方块）会被清除，只保留障碍和固定提示，其余方块回到托盘。
+    pub fn new(level: GameState) -> Self {
+        let mut level = level;
+        let clue_ids: Vec<u8> = level.clues.iter().map(|c| c.piece_id).collect();
+        for cell in level.board.cells_mut().iter_mut() {
+            if *cell > 0 && !clue_ids.contains(&(*cell as u8)) {
+                *cell = 0;
+            }
+        }
+        let mut placements = vec![None; level.pieces.len()];
+        for (idx, piece) in level.pieces.iter_mut().enumerate() {
+            piece.reset();
+            if let Some(clue) = level.clues.iter().find(|c| c.piece_id == piece.id) {
+                if clue.rotated {
+                    piece.rotate();
+                }
+                placements[idx] = Some(clue.clone());
+            }
+        }
+        level.used_pieces = placements.iter().map(|p| p.is_some()).collect();
+
+        Self {
+            board: level.board.clone(),
+            pieces: level.pieces.clone(),
+            placements,
+            history: Vec::new(),
+            started_at_ms: now_ms(),
+            finished_at_ms: None,
+            hints_used: 0,
+            level,
+        }
+    }
+
+    /// 初始关卡
+    pub fn level(&self) -> &GameState {
+        &self.level
+    }
+
+    /// 当前棋盘
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// 托盘中的方块
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    /// 操作历史
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    /// 用操作历史生成回放
+    pub fn replay(&self) -> Replay {
+        Replay::from_history(&self.level, &self.history)
+    }
+
+    /// 当前已放置的方块
+    pub fn placements(&self) -> Vec<Placement> {
+        self.placements.iter().flatten().cloned().collect()
+    }
+
+    /// 当前状态快照（与前端使用的GameState格式一致）
+    pub fn state(&self) -> GameState {
+        GameState {
+            board: self.board.clone(),
+            pieces: self.pieces.clone(),
+            used_pieces: self.placements.iter().map(|p| p.is_some()).collect(),
+            obstacle_positions: self.level.obstacle_positions.clone(),
+            clues: self.level.clues.clone(),
+        }
+    }
+
+    /// 把方块按托盘中的朝向放到指定位置
+    pub fn place(&mut self, piece_id: u8, row: usize, col: usize) -> Result<Placement, SessionError> {
+        self.ensure_not_solved()?;
+        let idx = self.index_of(piece_id)?;
+        if self.placements[idx].is_some() {
+            return Err(SessionError::PieceAlreadyPlaced(piece_id));
+        }
+
+        let piece = &self.pieces[idx];
+        if !self.board.can_place(piece, row, col) {
+            return Err(SessionError::InvalidPlacement { piece_id, row, col });
+        }
+
+        self.board.place(piece, row, col);
+        let placement = Placement {
+            piece_id,
+            row,
+            col,
+            rotated: piece.rotated,
+        };
+        self.placements[idx] = Some(placement.clone());
+        self.record(MoveKind::Place, piece_id, row, col, placement.rotated);
+
+        if self.is_solved() {
+            self.finished_at_ms = Some(now_ms());
+        }
+
+        Ok(placement)
+    }
+
+    /// 把方块从棋盘移回托盘
+    pub fn remove(&mut self, piece_id: u8) -> Result<Placement, SessionError> {
+        self.ensure_not_solved()?;
+        let idx = self.index_of(piece_id)?;
+        if self.level.is_clue(piece_id) {
+            return Err(SessionError::LockedPiece(piece_id));
+        }
+        let placement = self.placements[idx]
+            .take()
+            .ok_or(SessionError::PieceNotPlaced(piece_id))?;
+
+        self.board.remove(&self.pieces[idx], placement.row, placement.col);
+        self.record(MoveKind::Remove, piece_id, placement.row, placement.col, placement.rotated);
+
+        Ok(placement)
+    }
+
+    /// 移除覆盖指定格子的方块
+    pub fn remove_at(&mut self, row: usize, col: usize) -> Result<Placement, SessionError> {
+        match self.board.get(row, col) {
+            id if id > 0 => self.remove(id as u8),
+            _ => Err(SessionError::NoPieceAt { row, col }),
+        }
+    }
+
+    /// 旋转托盘中的方块，返回旋转后的状态
+    pub fn rotate_in_tray(&mut self, piece_id: u8) -> Result<bool, SessionError> {
+        self.ensure_not_solved()?;
+        let idx = self.index_of(piece_id)?;
+        if self.level.is_clue(piece_id) {
+            return Err(SessionError::LockedPiece(piece_id));
+        }
+        if self.placements[idx].is_some() {
+            return Err(SessionError::PieceAlreadyPlaced(piece_id));
+        }
+
+        self.pieces[idx].rotate();
+        let rotated = self.pieces[idx].rotated;
+        self.record(MoveKind::Rotate, piece_id, 0, 0, rotated);
+
+        Ok(rotated)
+    }
+
+    /// 是否已完成（所有方块都已放置且棋盘填满）
+    pub fn is_solved(&self) -> bool {
+        self.placements.iter().all(|p| p.is_some()) && self.board.is_full()
+    }
+
+    /// 提示：从当前棋盘出发求解，返回下一个应放置的方块
+    ///
+    /// 当前棋盘已无法完成时返回None（玩家需要先移除一些方块），这种情况不计入提示次数。
+    pub fn hint(&mut self) -> Option<Placement> {
+        if self.is_solved() {
+            return None;
+        }
+
+        let mut state = self.level.clone();
+        state.board = self.board.clone();
+        state.used_pieces = self.placements.iter().map(|p| p.is_some()).collect();
+        for piece in &mut state.pieces {
+            piece.reset();
+        }
+
+        let solution = match Solver::new(1).solve(&state) {
+            SolveResult::UniqueSolution(solution) => solution,
+            SolveResult::MultipleSolutions(mut solutions) => solutions.pop()?,
+            SolveResult::NoSolution => return None,
+        };
+        // 只有真正给出提示时才计数
+        let hint = solution.placements.into_iter().next()?;
+        self.hints_used += 1;
+        Some(hint)
+    }
+
+    /// 验证当前棋盘是关卡的合法解
+    pub fn verify(&self) -> Result<VerifiedSolution, VerificationError> {
+        verify_solution(&self.level, &self.board)
+    }
+
+    /// 当前进度
+    pub fn progress(&self) -> Progress {
+        let obstacle_cells = self.level.board.cells().iter().filter(|&&c| c < 0).count();
+        let filled_cells = self.board.cells().iter().filter(|&&c| c > 0).count();
+
+        Progress {
+            placed_pieces: self.placements.iter().filter(|p| p.is_some()).count(),
+            total_pieces: self.pieces.len(),
+            filled_cells,
+            total_cells: TOTAL_CELLS - obstacle_cells,
+            elapsed_ms: self.elapsed_ms(),
+            moves: self.history.len(),
+            hints_used: self.hints_used,
+            solved: self.is_solved(),
+        }
+    }
+
+    /// 已用时间（毫秒），完成后停止计时
+    pub fn elapsed_ms(&self) -> u64 {
+        let end = self.finished_at_ms.unwrap_or_else(now_ms);
+        end.saturating_sub(self.started_at_ms)
+    }
+
+    /// 重新开始（清空棋盘、历史和计时）
+    pub fn restart(&mut self) {
+        *self = Self::new(self.level.clone());
+    }
+
+    fn index_of(&self, piece_id: u8) -> Result<usize, SessionError> {
+        self.pieces
+            .iter()
+            .position(|p| p.id == piece_id)
+            .ok_or(SessionError::UnknownPiece(piece_id))
+    }
+
+    fn ensure_not_solved(&self) -> Result<(), SessionError> {
+        if self.finished_at_ms.is_some() {
+            return Err(SessionError::AlreadySolved);
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, kind: MoveKind, piece_id: u8, row: usize, col: usize, rotated: bool) {
+        let at_ms = now_ms().saturating_sub(self.started_at_ms);
+        self.history.push(Move {
+            kind,
+            piece_id,
+            row,
+            col,
+            rotated,
+            at_ms,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::get_standard_pieces;
+    use crate::solver::Solver;
+    use crate::types::SolveResult;
+
+    /// 顶行放置3个黑色障碍的关卡
+    fn sample_level() -> GameState {
+        let mut pieces = get_standard_pieces();
+        let mut board = Board::new();
+        board.set(0, 0, -1);
+        board.set(0, 1, -2);
+        board.set(0, 2, -2);
+        board.set(0, 3, -3);
+        board.set(0, 4, -3);
+        board.set(0, 5, -3);
+        pieces.drain(0..3);
+
+        let mut state = GameState::new(pieces);
+        state.board = board;
+        state.obstacle_positions = vec![(0, 0, 1), (0, 1, 2), (0, 3, 3)];
+        state
+    }
+
+    #[test]
+    fn test_place_and_remove() {
+        let mut session = Session::new(sample_level());
+
+        let placement = session.place(6, 1, 0).unwrap();
+        assert_eq!(placement, Placement { piece_id: 6, row: 1, col: 0, rotated: false });
+        assert_eq!(session.board().get(2, 1), 6);
+        assert!(session.state().used_pieces[2]);
+
+        // 同一方块不能放两次，不能覆盖障碍
+        assert_eq!(session.place(6, 4, 4), Err(SessionError::PieceAlreadyPlaced(6)));
+        assert!(matches!(session.place(7, 0, 5), Err(SessionError::InvalidPlacement { .. })));
+
+        // 空格和障碍上没有可移除的方块
+        assert_eq!(session.remove_at(5, 5), Err(SessionError::NoPieceAt { row: 5, col: 5 }));
+        assert_eq!(session.remove_at(0, 0), Err(SessionError::NoPieceAt { row: 0, col: 0 }));
+        assert_eq!(session.remove_at(2, 1).map(|p| p.piece_id), Ok(6));
+        session.place(6, 1, 0).unwrap();
+
+        session.remove(6).unwrap();
+        assert_eq!(session.board().get(2, 1), 0);
+        assert_eq!(session.remove(6), Err(SessionError::PieceNotPlaced(6)));
+        assert_eq!(session.history().len(), 4);
+    }
+
+    #[test]
+    fn test_rotate_in_tray() {
+        let mut session = Session::new(sample_level());
+
+        assert_eq!(session.rotate_in_tray(4), Ok(true));
+        let placement = session.place(4, 1, 0).unwrap();
+        assert!(placement.rotated);
+        assert_eq!(session.board().get(1, 3), 4);
+
+        // 已放置的方块不能在托盘中旋转
+        assert_eq!(session.rotate_in_tray(4), Err(SessionError::PieceAlreadyPlaced(4)));
+        assert_eq!(session.rotate_in_tray(1), Err(SessionError::UnknownPiece(1)));
+    }
+
+    #[test]
+    fn test_clue_pieces_are_locked() {
+        let mut level = sample_level();
+        assert!(level.add_clue(Placement { piece_id: 6, row: 1, col: 0, rotated: false }));
+        // 已使用或放不下的方块不能作为提示
+        assert!(!level.add_clue(Placement { piece_id: 6, row: 4, col: 4, rotated: false }));
+        assert!(!level.add_clue(Placement { piece_id: 7, row: 0, col: 5, rotated: false }));
+
+        let mut session = Session::new(level);
+        assert_eq!(session.board().get(2, 1), 6);
+        assert_eq!(session.progress().placed_pieces, 1);
+        assert_eq!(session.remove(6), Err(SessionError::LockedPiece(6)));
+        assert_eq!(session.rotate_in_tray(6), Err(SessionError::LockedPiece(6)));
+
+        // 重新开始后提示仍在棋盘上
+        session.place(4, 3, 0).unwrap();
+        session.restart();
+        assert_eq!(session.board().get(2, 1), 6);
+        assert_eq!(session.board().get(3, 0), 0);
+        assert_eq!(session.state().clues.len(), 1);
+    }
+
+    #[test]
+    fn test_hint() {
+        let mut session = Session::new(sample_level());
+
+        let hint = session.hint().expect("Empty level should have a hint");
+        if hint.rotated {
+            session.rotate_in_tray(hint.piece_id).unwrap();
+        }
+        session.place(hint.piece_id, hint.row, hint.col).unwrap();
+        assert_eq!(session.progress().hints_used, 1);
+
+        // 1x4竖放在(1,1)后，左侧留下长度为4的孤立竖条，无法完成
+        session.restart();
+        session.place(4, 1, 1).unwrap();
+        assert_eq!(session.hint(), None);
+        assert_eq!(session.progress().hints_used, 0);
+    }
+
+    #[test]
+    fn test_solve_session() {
+        let level = sample_level();
+        let solution = match Solver::new(1).solve(&level) {
+            SolveResult::UniqueSolution(s) => s,
+            SolveResult::MultipleSolutions(mut s) => s.remove(0),
+            SolveResult::NoSolution => panic!("Sample level should be solvable"),
+        };
+
+        let mut session = Session::new(level);
+        for placement in &solution.placements {
+            if placement.rotated {
+                session.rotate_in_tray(placement.piece_id).unwrap();
+            }
+            assert!(!session.is_solved());
+            session.place(placement.piece_id, placement.row, placement.col).unwrap();
+        }
+
+        assert!(session.is_solved());
+        assert!(session.verify().is_ok());
+        let progress = session.progress();
+        assert_eq!(progress.placed_pieces, 8);
+        assert_eq!(progress.filled_cells, progress.total_cells);
+        assert!(progress.solved);
+
+        // 完成后不再接受操作
+        assert_eq!(session.remove(6), Err(SessionError::AlreadySolved));
+    }
+}
@@ -0,0 +1,221 @@
+//! 棋盘形状模块 - 用掩码定义不规则的游戏区域（心形、阶梯等），区域外的格子都是空洞
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::generator::{GenerationOutcome, GenerationStrategy, Generator};
+use crate::parse::ParseBoardError;
+use crate::types::{Board, Difficulty, GameState, Piece};
+use crate::{BOARD_SIZE, TOTAL_CELLS, VOID_CELL};
+
+/// 棋盘形状掩码（第 row*8+col 位为1表示该格属于棋盘）
+///
+/// 文本形式每行8个字符，`#`/`■` 表示棋盘内，`.`/`·` 表示棋盘外：
+///
+/// ```
+/// use logic_core::shape::BoardMask;
+///
+/// let heart = BoardMask::heart();
+/// assert_eq!(heart.to_string().parse::<BoardMask>().unwrap(), heart);
+/// assert_eq!(heart.area(), 48);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BoardMask(u64);
+
+impl BoardMask {
+    /// 完整的8x8棋盘
+    pub const FULL: BoardMask = BoardMask(u64::MAX);
+
+    /// 从位掩码创建
+    pub fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// 位掩码
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    /// 格子是否属于棋盘
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        self.0 & (1 << (row * BOARD_SIZE + col)) != 0
+    }
+
+    /// 棋盘内的格子数
+    pub fn area(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// 心形（48格，尖端取两格宽，否则标准方块拼不满）
+    pub fn heart() -> Self {
+        Self::from_rows([
+            ".##..##.",
+            "########",
+            "########",
+            "########",
+            ".######.",
+            ".######.",
+            "..####..",
+            "..####..",
+        ])
+    }
+
+    /// 每两行加宽两格的阶梯（40格）
+    pub fn staircase() -> Self {
+        Self::from_rows([
+            "##......",
+            "##......",
+            "####....",
+            "####....",
+            "######..",
+            "######..",
+            "########",
+            "########",
+        ])
+    }
+
+    fn from_rows(rows: [&str; BOARD_SIZE]) -> Self {
+        let mut bits = 0;
+        for (row, line) in rows.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                if c == '#' {
+                    bits |= 1 << (row * BOARD_SIZE + col);
+                }
+            }
+        }
+        Self(bits)
+    }
+}
+
+impl Default for BoardMask {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl fmt::Display for BoardMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..BOARD_SIZE {
+            let line: Vec<&str> = (0..BOARD_SIZE).map(|col| if self.contains(row, col) { "#" } else { "." }).collect();
+            writeln!(f, "{}", line.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BoardMask {
+    type Err = ParseBoardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut bits = 0;
+        let mut row = 0;
+
+        for line in text.lines() {
+            let symbols: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+            if symbols.is_empty() {
+                continue;
+            }
+            if row >= BOARD_SIZE {
+                return Err(ParseBoardError::WrongRowCount(row + 1));
+            }
+            if symbols.len() != BOARD_SIZE {
+                return Err(ParseBoardError::WrongRowLength { row, len: symbols.len() });
+            }
+            for (col, &symbol) in symbols.iter().enumerate() {
+                match symbol {
+                    '#' | '■' => bits |= 1 << (row * BOARD_SIZE + col),
+                    '.' | '·' => {}
+                    _ => return Err(ParseBoardError::UnknownSymbol { row, col, symbol }),
+                }
+            }
+            row += 1;
+        }
+
+        if row != BOARD_SIZE {
+            return Err(ParseBoardError::WrongRowCount(row));
+        }
+        Ok(Self(bits))
+    }
+}
+
+impl Board {
+    /// 创建指定形状的空棋盘（形状外为空洞）
+    pub fn from_mask(mask: &BoardMask) -> Self {
+        let mut board = Board::new();
+        for idx in 0..TOTAL_CELLS {
+            if !mask.contains(idx / BOARD_SIZE, idx % BOARD_SIZE) {
+                board.cells[idx] = VOID_CELL;
+            }
+        }
+        board
+    }
+
+    /// 棋盘的形状（非空洞的格子）
+    pub fn mask(&self) -> BoardMask {
+        let bits = self
+            .cells()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &cell)| cell != VOID_CELL)
+            .fold(0u64, |bits, (idx, _)| bits | (1 << idx));
+        BoardMask(bits)
+    }
+}
+
+impl GameState {
+    /// 在指定形状的棋盘上创建游戏状态（方块总面积应等于形状面积，见 `verify_area`）
+    pub fn with_mask(pieces: Vec<Piece>, mask: &BoardMask) -> Self {
+        let mut state = GameState::new(pieces);
+        state.board = Board::from_mask(mask);
+        state
+    }
+}
+
+impl Generator {
+    /// 在指定形状的棋盘上生成关卡，方块集合按形状面积随机减少
+    pub fn generate_with_mask(&self, difficulty: Difficulty, mask: &BoardMask) -> Option<GameState> {
+        self.generate_with_mask_report(difficulty, mask, GenerationStrategy::Auto).level
+    }
+
+    /// 用指定方式在指定形状的棋盘上生成关卡，同时返回统计信息
+    pub fn generate_with_mask_report(&self, difficulty: Difficulty, mask: &BoardMask, strategy: GenerationStrategy) -> GenerationOutcome {
+        self.generate_on(difficulty, strategy, || Board::from_mask(mask))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::verify_area;
+    use crate::solver::Solver;
+
+    #[test]
+    fn test_mask_text_round_trip() {
+        for mask in [BoardMask::FULL, BoardMask::heart(), BoardMask::staircase()] {
+            assert_eq!(mask.to_string().parse::<BoardMask>(), Ok(mask));
+            assert_eq!(Board::from_mask(&mask).mask(), mask);
+            assert!(Board::from_mask(&mask).is_connected());
+        }
+        assert_eq!(
+            "# # x".parse::<BoardMask>(),
+            Err(ParseBoardError::WrongRowLength { row: 0, len: 3 })
+        );
+    }
+
+    #[test]
+    fn test_generate_shaped_levels() {
+        let generator = Generator::with_seed(1);
+        for mask in [BoardMask::heart(), BoardMask::staircase()] {
+            let level = generator.generate_with_mask(Difficulty::Medium, &mask).expect("Shaped level should generate");
+
+            assert_eq!(level.board.mask(), mask);
+            assert!(verify_area(&level.pieces, &level.board));
+            let crate::types::SolveResult::UniqueSolution(solution) = Solver::new(2).solve(&level) else {
+                panic!("Shaped level should be unique");
+            };
+            assert_eq!(solution.board.mask(), mask);
+        }
+    }
+}
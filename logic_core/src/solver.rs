@@ -1,39 +1,232 @@
 //! DFS求解器模块
 
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
 use crate::types::{Board, Piece, Placement, Solution, SolveResult, GameState};
+use crate::{BOARD_SIZE, TOTAL_CELLS};
+
+/// 分支策略：每一层搜索选哪个格子或哪个方块来展开
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BranchingStrategy {
+    /// 按行优先的第一个空格，方块左上角对齐该格（默认，搜索顺序稳定）
+    #[default]
+    FirstEmpty,
+    /// 合法覆盖方式最少的空格（有空格无法覆盖时立即回溯）
+    MostConstrainedCell,
+    /// 合法位置最少的方块（方块总面积与空格数不等、有方块用不上时退回 `MostConstrainedCell`）
+    PieceFirst,
+}
+
+/// 一次搜索的统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+    /// 找到的解的数量（最多max_solutions个）
+    pub solutions: usize,
+    /// 访问的搜索节点数
+    pub nodes: u64,
+    /// 直接从置换表得到结果的节点数
+    pub memo_hits: u64,
+}
 
 /// DFS求解器
 pub struct Solver {
     /// 最多找多少个解（1=找一个，2=检测唯一性）
     max_solutions: usize,
+    /// 分支策略
+    branching: BranchingStrategy,
+    /// 是否使用置换表
+    memo: bool,
+}
+
+/// 一步放置：第几个方块、是否在当前朝向上再旋转、左上角位置
+#[derive(Debug, Clone, Copy)]
+struct Move {
+    piece: usize,
+    rotate: bool,
+    row: usize,
+    col: usize,
+}
+
+/// 置换表用的哈希（键只是几个位掩码，不需要抗碰撞攻击的SipHash）
+#[derive(Default)]
+struct KeyHasher(u64);
+
+impl Hasher for KeyHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(byte as u64);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = (self.0.rotate_left(5) ^ value).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write_u64(value as u64);
+        self.write_u64((value >> 64) as u64);
+    }
+}
+
+type Memo = HashMap<(u64, u128), usize, BuildHasherDefault<KeyHasher>>;
+
+/// 搜索过程中的可变状态
+struct SearchState {
+    /// 本次搜索实际使用的分支策略
+    branching: BranchingStrategy,
+    board: Board,
+    pieces: Vec<Piece>,
+    /// 前一个同种方块的下标（同种方块只按顺序使用，避免互换同种方块得到重复的解）
+    twins: Vec<Option<usize>>,
+    used: Vec<bool>,
+    placements: Vec<Placement>,
+    /// 每层搜索复用的候选放置列表（避免每个节点分配内存）
+    buffers: Vec<Vec<Move>>,
+    /// 置换表：(已占格子, 已用方块) → 剩余局面的解数（不超过max_solutions）
+    memo: Option<Memo>,
+    /// 已占格子的位掩码（随放置增量更新）
+    occupied: u64,
+    /// 已用方块的位掩码
+    used_mask: u128,
+    /// 已找到的解的数量
+    found: usize,
+    nodes: u64,
+    memo_hits: u64,
+}
+
+impl SearchState {
+    /// 置换表的键：剩余局面只取决于哪些格子被占、哪些方块已用
+    fn key(&self) -> (u64, u128) {
+        (self.occupied, self.used_mask)
+    }
+
+    /// 放置覆盖的格子的位掩码
+    fn move_bits(&self, mv: &Move) -> u64 {
+        let (height, width) = self.extent(mv);
+        let row_bits = (1u64 << width) - 1;
+        (mv.row..mv.row + height).fold(0, |bits, row| bits | row_bits << (row * BOARD_SIZE + mv.col))
+    }
+
+    /// 执行一步放置
+    fn apply(&mut self, mv: Move) {
+        self.occupied |= self.move_bits(&mv);
+        self.used_mask |= 1 << mv.piece;
+        let piece = &mut self.pieces[mv.piece];
+        if mv.rotate {
+            piece.rotate();
+        }
+        self.board.place(piece, mv.row, mv.col);
+        self.used[mv.piece] = true;
+        self.placements.push(Placement {
+            piece_id: piece.id,
+            row: mv.row,
+            col: mv.col,
+            rotated: piece.rotated,
+        });
+    }
+
+    /// 撤销一步放置
+    fn undo(&mut self, mv: Move) {
+        self.placements.pop();
+        self.board.remove(&self.pieces[mv.piece], mv.row, mv.col);
+        self.used[mv.piece] = false;
+        if mv.rotate {
+            self.pieces[mv.piece].rotate();
+        }
+        self.occupied &= !self.move_bits(&mv);
+        self.used_mask &= !(1 << mv.piece);
+    }
+
+    /// 方块当前是否可用（未使用，且前一个同种方块已用过）
+    fn available(&self, i: usize) -> bool {
+        !self.used[i] && self.twins[i].is_none_or(|j| self.used[j])
+    }
+
+    /// 方块的两种朝向（正方形只有一种）
+    fn rotations(&self, i: usize) -> &'static [bool] {
+        if self.pieces[i].width == self.pieces[i].height { &[false] } else { &[false, true] }
+    }
+
+    /// 方块按指定朝向能否放在 (row, col)
+    fn fits(&mut self, i: usize, rotate: bool, row: usize, col: usize) -> bool {
+        if rotate {
+            self.pieces[i].rotate();
+        }
+        let fits = self.board.can_place(&self.pieces[i], row, col);
+        if rotate {
+            self.pieces[i].rotate();
+        }
+        fits
+    }
+
+    /// 所有可用方块的所有合法放置
+    fn legal_moves(&mut self, moves: &mut Vec<Move>) {
+        for piece in 0..self.pieces.len() {
+            if !self.available(piece) {
+                continue;
+            }
+            for &rotate in self.rotations(piece) {
+                for row in 0..BOARD_SIZE {
+                    for col in 0..BOARD_SIZE {
+                        if self.fits(piece, rotate, row, col) {
+                            moves.push(Move { piece, rotate, row, col });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// 放置后方块的 (高, 宽)
+    fn extent(&self, mv: &Move) -> (usize, usize) {
+        let piece = &self.pieces[mv.piece];
+        if mv.rotate { (piece.width, piece.height) } else { (piece.height, piece.width) }
+    }
+
+    /// 放置是否覆盖 (row, col)
+    fn covers(&self, mv: &Move, row: usize, col: usize) -> bool {
+        let (height, width) = self.extent(mv);
+        (mv.row..mv.row + height).contains(&row) && (mv.col..mv.col + width).contains(&col)
+    }
 }
 
 impl Solver {
     /// 创建新的求解器
     pub fn new(max_solutions: usize) -> Self {
-        Self { max_solutions }
+        Self { max_solutions, branching: BranchingStrategy::default(), memo: false }
     }
 
-    /// 创建默认求解器（最多找2个解，用于验证唯一性）
-    pub fn default() -> Self {
-        Self::new(2)
+    /// 使用指定的分支策略（不影响解的数量，只影响搜索顺序和节点数）
+    pub fn with_branching(mut self, branching: BranchingStrategy) -> Self {
+        self.branching = branching;
+        self
+    }
+
+    /// 是否使用置换表（同一次搜索中，不同放置顺序到达的相同局面只搜索一次）
+    ///
+    /// 统计解数时缓存每个局面的解数；求解时需要具体的解，只缓存无解的局面。
+    /// 查表本身有开销，只在重复局面多（例如穷举大量解）时更快。
+    /// 同种方块重复且使用 `PieceFirst` 时局面的解数与放置顺序有关，此时不使用置换表。
+    pub fn with_memo(mut self, memo: bool) -> Self {
+        self.memo = memo;
+        self
     }
 
     /// 求解游戏
     pub fn solve(&self, state: &GameState) -> SolveResult {
-        let mut board = state.board.clone();
-        let mut pieces: Vec<Piece> = state.pieces
-            .iter()
-            .enumerate()
-            .filter(|(idx, _)| !state.used_pieces[*idx])
-            .map(|(_, p)| p.clone())
-            .collect();
-
-        let mut used = vec![false; pieces.len()];
         let mut solutions = Vec::new();
-        let mut placements_stack = Vec::new();
 
-        self.dfs(&mut board, &mut pieces, &mut used, &mut solutions, &mut placements_stack);
+        self.search(state, &mut |board, placements| {
+            solutions.push(Solution {
+                board: board.clone(),
+                placements: placements.to_vec(),
+            });
+        });
 
         match solutions.len() {
             0 => SolveResult::NoSolution,
@@ -42,103 +235,229 @@ impl Solver {
         }
     }
 
-    /// DFS回溯核心算法
-    fn dfs(
-        &self,
-        board: &mut Board,
-        pieces: &mut [Piece],
-        used: &mut [bool],
-        solutions: &mut Vec<Solution>,
-        placements: &mut Vec<Placement>,
-    ) -> bool {
-        // 如果已找到足够多的解，提前返回
-        if solutions.len() >= self.max_solutions {
-            return true;
+    /// 统计解的数量（最多统计到max_solutions个，不保存解）
+    pub fn count_solutions(&self, state: &GameState) -> usize {
+        self.search_stats(state).solutions
+    }
+
+    /// 统计解的数量和访问的搜索节点数
+    pub fn search_stats(&self, state: &GameState) -> SearchStats {
+        if self.max_solutions == 0 {
+            return SearchStats::default();
         }
 
-        // 如果棋盘已填满，找到一个解
-        if board.is_full() {
-            solutions.push(Solution {
-                board: board.clone(),
-                placements: placements.clone(),
-            });
-            return solutions.len() >= self.max_solutions;
+        let mut search = self.start(state);
+        let solutions = if search.memo.is_some() {
+            self.count(&mut search)
+        } else {
+            self.dfs(&mut search, &mut |_, _| {});
+            search.found
+        };
+
+        SearchStats { solutions, nodes: search.nodes, memo_hits: search.memo_hits }
+    }
+
+    /// 搜索解，每找到一个解调用一次 `on_solution`，找到max_solutions个时停止
+    fn search(&self, state: &GameState, on_solution: &mut dyn FnMut(&Board, &[Placement])) {
+        if self.max_solutions == 0 {
+            return;
         }
 
-        // 找到第一个空格
-        let (row, col) = match board.find_first_empty() {
-            Some(pos) => pos,
-            None => return false,
+        let mut search = self.start(state);
+        self.dfs(&mut search, on_solution);
+    }
+
+    /// 初始化搜索状态（已使用的方块不参与搜索）
+    fn start(&self, state: &GameState) -> SearchState {
+        let pieces: Vec<Piece> = state.pieces
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !state.used_pieces[*idx])
+            .map(|(_, p)| p.clone())
+            .collect();
+        let twins: Vec<Option<usize>> = (0..pieces.len())
+            .map(|i| (0..i).rev().find(|&j| pieces[j].same_kind(&pieces[i])))
+            .collect();
+        // 按方块分支要求每个方块都用上，有多余的方块时只能按格子分支
+        let area: usize = pieces.iter().map(Piece::area).sum();
+        let branching = match self.branching {
+            BranchingStrategy::PieceFirst if area != state.board.count_empty() => BranchingStrategy::MostConstrainedCell,
+            branching => branching,
         };
+        let order_dependent = branching == BranchingStrategy::PieceFirst && twins.iter().any(Option::is_some);
+
+        SearchState {
+            branching,
+            board: state.board.clone(),
+            used: vec![false; pieces.len()],
+            buffers: vec![Vec::new(); pieces.len() + 1],
+            memo: (self.memo && !order_dependent).then(Memo::default),
+            occupied: state.board.cells().iter().enumerate().filter(|&(_, &cell)| cell != 0).fold(0, |bits, (idx, _)| bits | (1 << idx)),
+            used_mask: 0,
+            pieces,
+            twins,
+            placements: Vec::new(),
+            found: 0,
+            nodes: 0,
+            memo_hits: 0,
+        }
+    }
 
-        // 尝试每个未使用的方块
-        for i in 0..pieces.len() {
-            if used[i] {
-                continue;
+    /// DFS回溯核心算法，返回true表示已找到足够多的解
+    fn dfs(&self, search: &mut SearchState, on_solution: &mut dyn FnMut(&Board, &[Placement])) -> bool {
+        search.nodes += 1;
+
+        // 如果棋盘已填满，找到一个解
+        if search.board.is_full() {
+            search.found += 1;
+            on_solution(&search.board, &search.placements);
+            return search.found >= self.max_solutions;
+        }
+
+        // 置换表里只记录无解的局面
+        let key = search.memo.as_ref().map(|_| search.key());
+        let dead = matches!((&search.memo, key), (Some(memo), Some(key)) if memo.get(&key) == Some(&0));
+        if dead {
+            search.memo_hits += 1;
+            return false;
+        }
+        let found_before = search.found;
+
+        let depth = search.placements.len();
+        let mut moves = std::mem::take(&mut search.buffers[depth]);
+        moves.clear();
+        self.branch(search, &mut moves);
+
+        let mut done = false;
+        for &mv in &moves {
+            search.apply(mv);
+            done = self.dfs(search, on_solution);
+            search.undo(mv);
+
+            if done {
+                break; // 已找到足够多的解
             }
+        }
+        search.buffers[depth] = moves;
 
-            // 检查是否是正方形（提前，避免后面重复检查）
-            let is_square = pieces[i].width == pieces[i].height;
+        if let (Some(memo), Some(key)) = (&mut search.memo, key) {
+            if search.found == found_before {
+                memo.insert(key, 0);
+            }
+        }
+        done
+    }
 
-            // 尝试两种朝向
-            for rotation in 0..2 {
-                if rotation == 1 {
-                    pieces[i].rotate();
-                }
+    /// 带置换表的计数搜索，返回当前局面的解数（不超过max_solutions）
+    ///
+    /// 各子局面的解数都截断到max_solutions，加起来再截断仍然等于总解数截断后的值。
+    fn count(&self, search: &mut SearchState) -> usize {
+        search.nodes += 1;
 
-                // 检查是否可以放置
-                if board.can_place(&pieces[i], row, col) {
-                    let piece_id = pieces[i].id;
-                    let is_rotated = pieces[i].rotated;
-
-                    // 放置方块
-                    board.place(&pieces[i], row, col);
-                    used[i] = true;
-                    placements.push(Placement {
-                        piece_id,
-                        row,
-                        col,
-                        rotated: is_rotated,
-                    });
-
-                    // 递归搜索
-                    if self.dfs(board, pieces, used, solutions, placements) {
-                        // 恢复状态
-                        placements.pop();
-                        board.remove(&pieces[i], row, col);
-                        used[i] = false;
-
-                        if rotation == 1 {
-                            pieces[i].rotate();
-                        }
+        if search.board.is_full() {
+            return 1;
+        }
 
-                        return true; // 已找到足够多的解
-                    }
+        let key = search.key();
+        if let Some(&count) = search.memo.as_ref().and_then(|memo| memo.get(&key)) {
+            search.memo_hits += 1;
+            return count;
+        }
 
-                    // 回溯
-                    placements.pop();
-                    board.remove(&pieces[i], row, col);
-                    used[i] = false;
-                }
+        let depth = search.placements.len();
+        let mut moves = std::mem::take(&mut search.buffers[depth]);
+        moves.clear();
+        self.branch(search, &mut moves);
 
-                if rotation == 1 {
-                    pieces[i].rotate();
+        let mut total = 0;
+        for &mv in &moves {
+            search.apply(mv);
+            total += self.count(search);
+            search.undo(mv);
+
+            if total >= self.max_solutions {
+                total = self.max_solutions;
+                break;
+            }
+        }
+        search.buffers[depth] = moves;
+
+        if let Some(memo) = &mut search.memo {
+            memo.insert(key, total);
+        }
+        total
+    }
+
+    /// 按分支策略列出当前节点要尝试的放置（为空表示死路）
+    fn branch(&self, search: &mut SearchState, moves: &mut Vec<Move>) {
+        match search.branching {
+            BranchingStrategy::FirstEmpty => {
+                // 第一个空格一定是某个方块的左上角
+                let Some((row, col)) = search.board.find_first_empty() else {
+                    return;
+                };
+                for piece in 0..search.pieces.len() {
+                    if !search.available(piece) {
+                        continue;
+                    }
+                    for &rotate in search.rotations(piece) {
+                        if search.fits(piece, rotate, row, col) {
+                            moves.push(Move { piece, rotate, row, col });
+                        }
+                    }
+                }
+            }
+            BranchingStrategy::MostConstrainedCell => {
+                search.legal_moves(moves);
+                let mut coverage = [0usize; TOTAL_CELLS];
+                for mv in moves.iter() {
+                    let (height, width) = search.extent(mv);
+                    for row in mv.row..mv.row + height {
+                        for col in mv.col..mv.col + width {
+                            coverage[row * BOARD_SIZE + col] += 1;
+                        }
+                    }
                 }
 
-                // 如果是正方形，不需要尝试旋转
-                if is_square {
-                    break;
+                let cell = (0..TOTAL_CELLS)
+                    .filter(|&idx| search.board.cells()[idx] == 0)
+                    .min_by_key(|&idx| coverage[idx]);
+                match cell {
+                    Some(idx) => moves.retain(|mv| search.covers(mv, idx / BOARD_SIZE, idx % BOARD_SIZE)),
+                    None => moves.clear(),
                 }
             }
+            BranchingStrategy::PieceFirst => {
+                search.legal_moves(moves);
+                let fewest = (0..search.pieces.len())
+                    .filter(|&piece| search.available(piece))
+                    .min_by_key(|&piece| moves.iter().filter(|mv| mv.piece == piece).count());
+                let Some(piece) = fewest else {
+                    moves.clear();
+                    return;
+                };
+                // 同种方块的放置位置必须按行优先递增，否则同一组位置会按不同顺序分给同种方块而重复计数
+                let after = search.twins[piece].and_then(|twin| {
+                    let id = search.pieces[twin].id;
+                    search.placements.iter().find(|p| p.piece_id == id).map(|p| (p.row, p.col))
+                });
+                moves.retain(|mv| mv.piece == piece && after.is_none_or(|pos| (mv.row, mv.col) > pos));
+            }
         }
+    }
+}
 
-        false
+impl Default for Solver {
+    /// 创建默认求解器（最多找2个解，用于验证唯一性）
+    fn default() -> Self {
+        Self::new(2)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use crate::piece::get_standard_pieces;
 
     #[test]
@@ -201,29 +520,146 @@ mod tests {
     #[test]
     fn test_solver_impossible_case() {
         let solver = Solver::new(1);
-        let mut pieces = get_standard_pieces();
-        let mut board = Board::new();
 
         // 创建一个不可能解决的配置：
-        // 留出3个不连续的1x1空格，但移除所有1x1的piece
+        // 留出3个不连续的1x1空格，其余都是障碍（非标准障碍都记为-1，1x1方块从托盘中移除）
         // 这样剩余的piece无法填充这些空格
-        for row in 0..8 {
-            for col in 0..8 {
-                if (row, col) != (0, 0) && (row, col) != (3, 3) && (row, col) != (7, 7) {
-                    board.set(row, col, -1);
+        let state: GameState = "\
+            . # # # # # # #
+            # # # # # # # #
+            # # # # # # # #
+            # # # . # # # #
+            # # # # # # # #
+            # # # # # # # #
+            # # # # # # # #
+            # # # # # # # ."
+            .parse()
+            .unwrap();
+        assert!(state.pieces.iter().all(|p| p.id != 1));
+
+        let result = solver.solve(&state);
+
+        // 这种情况应该无解，因为有3个孤立的1x1空格，但没有1x1的piece
+        assert!(matches!(result, SolveResult::NoSolution));
+        assert_eq!(solver.count_solutions(&state), 0);
+    }
+
+    #[test]
+    fn test_identical_pieces_are_not_permuted() {
+        // 2×3区域用三个1×2多米诺铺满有3种方式，互换多米诺不算新解
+        let domino = Piece::new(1, 1, 2, crate::types::Color::Black2);
+        let mut state = GameState::new(crate::piece::expand_inventory(&[(domino, 3)]));
+        for row in 0..crate::BOARD_SIZE {
+            for col in 0..crate::BOARD_SIZE {
+                if row >= 2 || col >= 3 {
+                    state.board.set(row, col, crate::VOID_CELL);
                 }
             }
         }
 
-        // 移除1x1的piece (ID=1)，使得无法填充这3个孤立的空格
-        pieces.retain(|p| p.id != 1);
+        for strategy in [BranchingStrategy::FirstEmpty, BranchingStrategy::MostConstrainedCell, BranchingStrategy::PieceFirst] {
+            for memo in [false, true] {
+                let solver = Solver::new(100).with_branching(strategy).with_memo(memo);
+                assert_eq!(solver.count_solutions(&state), 3, "{:?} memo={}", strategy, memo);
+            }
+        }
+        let SolveResult::MultipleSolutions(solutions) = Solver::new(100).solve(&state) else {
+            panic!("Expected multiple solutions");
+        };
+        for solution in &solutions {
+            assert!(crate::verify::verify_solution(&state, &solution.board).is_ok());
+        }
+    }
 
-        let mut state = GameState::new(pieces);
-        state.board = board;
+    #[test]
+    fn test_strategies_agree_with_surplus_pieces() {
+        // 1×3长条，托盘里有1×1、1×2、1×3：单独用1×3，或1×1和1×2按两种顺序，共3个解
+        let mut state = GameState::new(get_standard_pieces()[..3].to_vec());
+        for row in 0..crate::BOARD_SIZE {
+            for col in 0..crate::BOARD_SIZE {
+                if row > 0 || col >= 3 {
+                    state.board.set(row, col, crate::VOID_CELL);
+                }
+            }
+        }
 
-        let result = solver.solve(&state);
+        for strategy in [BranchingStrategy::FirstEmpty, BranchingStrategy::MostConstrainedCell, BranchingStrategy::PieceFirst] {
+            for memo in [false, true] {
+                let solver = Solver::new(100).with_branching(strategy).with_memo(memo);
+                assert_eq!(solver.count_solutions(&state), 3, "{:?} memo={}", strategy, memo);
+            }
+        }
+    }
 
-        // 这种情况应该无解，因为有3个孤立的1x1空格，但没有1x1的piece
-        assert!(matches!(result, SolveResult::NoSolution));
+    #[test]
+    fn test_memo_counts_match_plain_search() {
+        let state = GameState::new(get_standard_pieces());
+
+        for limit in [1, 2, 500] {
+            let plain = Solver::new(limit).search_stats(&state);
+            let memo = Solver::new(limit).with_memo(true).search_stats(&state);
+            assert_eq!(memo.solutions, plain.solutions);
+            assert_eq!(plain.memo_hits, 0);
+        }
+
+        // 穷举时相同局面会被反复到达
+        let memo = Solver::new(500).with_memo(true).search_stats(&state);
+        assert!(memo.memo_hits > 0);
+        assert!(memo.nodes < Solver::new(500).search_stats(&state).nodes);
+        assert!(matches!(Solver::new(3).with_memo(true).solve(&state), SolveResult::MultipleSolutions(s) if s.len() == 3));
+    }
+
+    #[test]
+    fn test_count_solutions_respects_limit() {
+        let state = GameState::new(get_standard_pieces());
+
+        assert_eq!(Solver::new(5).count_solutions(&state), 5);
+        assert_eq!(Solver::new(0).count_solutions(&state), 0);
+        match Solver::new(3).solve(&state) {
+            SolveResult::MultipleSolutions(solutions) => assert_eq!(solutions.len(), 3),
+            _ => panic!("Empty board should have multiple solutions"),
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(24))]
+
+        #[test]
+        fn prop_solutions_verify_and_match_reference(level in crate::testing::level()) {
+            let count = Solver::new(2).count_solutions(&level);
+            prop_assert_eq!(count, crate::testing::reference_count(&level, 2));
+            for strategy in [BranchingStrategy::MostConstrainedCell, BranchingStrategy::PieceFirst] {
+                let stats = Solver::new(2).with_branching(strategy).search_stats(&level);
+                prop_assert_eq!(stats.solutions, count);
+                prop_assert!(stats.nodes > 0);
+            }
+            prop_assert_eq!(Solver::new(2).with_memo(true).count_solutions(&level), count);
+            let memo_solve = match Solver::new(2).with_memo(true).solve(&level) {
+                SolveResult::NoSolution => 0,
+                SolveResult::UniqueSolution(_) => 1,
+                SolveResult::MultipleSolutions(s) => s.len(),
+            };
+            prop_assert_eq!(memo_solve, count);
+
+            let solutions = match Solver::new(2).solve(&level) {
+                SolveResult::NoSolution => Vec::new(),
+                SolveResult::UniqueSolution(s) => vec![s],
+                SolveResult::MultipleSolutions(s) => s,
+            };
+            prop_assert_eq!(solutions.len(), count);
+            for solution in &solutions {
+                let verified = crate::verify::verify_solution(&level, &solution.board);
+                prop_assert!(verified.is_ok(), "{:?}", verified);
+            }
+        }
+
+        #[test]
+        fn prop_surplus_pieces_match_reference(level in crate::testing::surplus_level()) {
+            let count = crate::testing::reference_count(&level, 2);
+            for strategy in [BranchingStrategy::FirstEmpty, BranchingStrategy::MostConstrainedCell, BranchingStrategy::PieceFirst] {
+                prop_assert_eq!(Solver::new(2).with_branching(strategy).count_solutions(&level), count);
+                prop_assert_eq!(Solver::new(2).with_branching(strategy).with_memo(true).count_solutions(&level), count);
+            }
+        }
     }
 }
@@ -0,0 +1,146 @@
+//! 测试工具 - proptest策略和用于交叉验证的参考求解器（仅测试时编译）
+
+use proptest::prelude::*;
+
+use crate::generator::state_from_obstacle_board;
+use crate::piece::get_standard_pieces;
+use crate::types::{Board, GameState, Piece, Placement};
+use crate::{BOARD_SIZE, OBSTACLE_COUNT};
+
+/// 任意标准方块（可能旋转）
+pub(crate) fn piece() -> impl Strategy<Value = Piece> {
+    (prop::sample::select(get_standard_pieces()), any::<bool>()).prop_map(|(mut piece, rotated)| {
+        if rotated {
+            piece.rotate();
+        }
+        piece
+    })
+}
+
+/// 任意放置位置（不保证放得下）
+pub(crate) fn placement() -> impl Strategy<Value = Placement> {
+    (1..=get_standard_pieces().len() as u8, 0..BOARD_SIZE, 0..BOARD_SIZE, any::<bool>())
+        .prop_map(|(piece_id, row, col, rotated)| Placement { piece_id, row, col, rotated })
+}
+
+/// 把放置序列依次摆到棋盘上，放不下或重复的方块跳过；`as_obstacle` 为true时记为障碍
+fn apply_placements(placements: &[(Placement, bool)]) -> Board {
+    let pieces = get_standard_pieces();
+    let mut board = Board::new();
+    let mut placed = Vec::new();
+
+    for (placement, as_obstacle) in placements {
+        if placed.contains(&placement.piece_id) {
+            continue;
+        }
+        let mut piece = pieces[placement.piece_id as usize - 1].clone();
+        if placement.rotated {
+            piece.rotate();
+        }
+        if !board.can_place(&piece, placement.row, placement.col) {
+            continue;
+        }
+        board.place(&piece, placement.row, placement.col);
+        if *as_obstacle {
+            for r in placement.row..placement.row + piece.height {
+                for c in placement.col..placement.col + piece.width {
+                    board.set(r, c, -(piece.id as i8));
+                }
+            }
+        }
+        placed.push(placement.piece_id);
+    }
+
+    board
+}
+
+/// 任意合法棋盘：随机摆放若干标准方块，部分作为障碍
+pub(crate) fn board() -> impl Strategy<Value = Board> {
+    prop::collection::vec((placement(), any::<bool>()), 0..12).prop_map(|placements| apply_placements(&placements))
+}
+
+/// 任意标准关卡：三个黑色障碍块随机摆放（可能无解或多解）
+pub(crate) fn level() -> impl Strategy<Value = GameState> {
+    prop::collection::vec((0..BOARD_SIZE, 0..BOARD_SIZE, any::<bool>()), OBSTACLE_COUNT)
+        .prop_map(|positions| {
+            let placements: Vec<(Placement, bool)> = positions
+                .into_iter()
+                .enumerate()
+                .map(|(i, (row, col, rotated))| (Placement { piece_id: i as u8 + 1, row, col, rotated }, true))
+                .collect();
+            apply_placements(&placements)
+        })
+        .prop_filter("all obstacles placed", |board| board.cells().iter().filter(|&&c| c < 0).count() == 6)
+        .prop_map(state_from_obstacle_board)
+}
+
+/// 任意标准关卡，拿掉一个障碍并把三个障碍方块都放回托盘（方块总面积大于空格数，有方块用不上）
+pub(crate) fn surplus_level() -> impl Strategy<Value = GameState> {
+    (level(), 1..=OBSTACLE_COUNT as i8).prop_map(|(mut state, id)| {
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                if state.board.get(row, col) == -id {
+                    state.board.set(row, col, 0);
+                }
+            }
+        }
+        for piece in &get_standard_pieces()[..OBSTACLE_COUNT] {
+            state.pieces.push(piece.clone());
+            state.used_pieces.push(false);
+        }
+        state
+    })
+}
+
+/// 参考求解器：和 `Solver` 搜索顺序相反（从最后一个空格开始，方块右下角对齐该格），
+/// 统计解的数量（最多 `limit` 个），用于交叉验证
+pub(crate) fn reference_count(state: &GameState, limit: usize) -> usize {
+    fn go(board: &mut Board, pieces: &mut [(Piece, bool)], limit: usize, count: &mut usize) {
+        let Some(idx) = board.cells().iter().rposition(|&c| c == 0) else {
+            *count += 1;
+            return;
+        };
+        let (row, col) = (idx / BOARD_SIZE, idx % BOARD_SIZE);
+
+        for i in 0..pieces.len() {
+            if pieces[i].1 {
+                continue;
+            }
+            let mut piece = pieces[i].0.clone();
+            piece.reset();
+            let orientations = if piece.width == piece.height { 1 } else { 2 };
+            for rotation in 0..orientations {
+                if rotation == 1 {
+                    piece.rotate();
+                }
+                if row + 1 < piece.height || col + 1 < piece.width {
+                    continue;
+                }
+                let (top, left) = (row + 1 - piece.height, col + 1 - piece.width);
+                if !board.can_place(&piece, top, left) {
+                    continue;
+                }
+
+                board.place(&piece, top, left);
+                pieces[i].1 = true;
+                go(board, pieces, limit, count);
+                pieces[i].1 = false;
+                board.remove(&piece, top, left);
+                if *count >= limit {
+                    return;
+                }
+            }
+        }
+    }
+
+    let mut board = state.board.clone();
+    let mut pieces: Vec<(Piece, bool)> = state
+        .pieces
+        .iter()
+        .zip(&state.used_pieces)
+        .map(|(p, &used)| (p.clone(), used))
+        .collect();
+    let mut count = 0;
+    go(&mut board, &mut pieces, limit, &mut count);
+    count
+}
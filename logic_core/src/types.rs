@@ -1,7 +1,10 @@
 //! 核心数据类型定义
 
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
-use crate::{BOARD_SIZE, TOTAL_CELLS};
+use crate::{BOARD_SIZE, OBSTACLE_COUNT, TOTAL_CELLS, VOID_CELL};
 
 /// 方块颜色（每个方块独立颜色，同色系内有深浅区分）
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -46,6 +49,11 @@ impl Color {
         }
     }
 
+    /// 获取颜色的RGB分量（与to_css一致）
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        parse_hex_rgb(self.to_css())
+    }
+
     /// 获取色系名称
     pub fn family(&self) -> &'static str {
         match self {
@@ -58,6 +66,12 @@ impl Color {
     }
 }
 
+/// 解析 `#rrggbb` 形式的颜色，无法解析的分量记为0
+pub(crate) fn parse_hex_rgb(hex: &str) -> (u8, u8, u8) {
+    let channel = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok()).unwrap_or(0);
+    (channel(1), channel(3), channel(5))
+}
+
 /// 方块
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Piece {
@@ -108,13 +122,19 @@ impl Piece {
     pub fn area(&self) -> usize {
         self.width * self.height
     }
+
+    /// 是否与另一个方块同种（原始尺寸和颜色相同，可以互换）
+    pub fn same_kind(&self, other: &Piece) -> bool {
+        (self.original_width, self.original_height, self.color) == (other.original_width, other.original_height, other.color)
+    }
 }
 
 /// 棋盘
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Board {
     /// 64个格子的状态
-    /// -1 = 障碍格（黑色起始块）
+    /// -1..-11 = 障碍格（-piece_id）
+    /// VOID_CELL = 空洞（不属于棋盘）
     /// 0 = 空格
     /// 1-11 = 对应piece_id
     #[serde(with = "board_cells_serde")]
@@ -176,7 +196,7 @@ impl Board {
     /// 检查是否可以在指定位置放置方块
     pub fn can_place(&self, piece: &Piece, row: usize, col: usize) -> bool {
         // 检查是否越界
-        if row + piece.height > BOARD_SIZE || col + piece.width > BOARD_SIZE {
+        if row.saturating_add(piece.height) > BOARD_SIZE || col.saturating_add(piece.width) > BOARD_SIZE {
             return false;
         }
 
@@ -244,6 +264,16 @@ impl Board {
     pub fn count_empty(&self) -> usize {
         self.cells.iter().filter(|&&cell| cell == 0).count()
     }
+
+    /// 检查指定位置是否为空洞
+    pub fn is_void(&self, row: usize, col: usize) -> bool {
+        self.get(row, col) == VOID_CELL
+    }
+
+    /// 计算空洞数量
+    pub fn count_void(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell == VOID_CELL).count()
+    }
 }
 
 impl Default for Board {
@@ -290,12 +320,15 @@ pub enum SolveResult {
 pub struct GameState {
     /// 当前棋盘状态
     pub board: Board,
-    /// 所有方块
+    /// 所有方块（ID互不相同，同种方块可以有多个，见 [`Piece::same_kind`]）
     pub pieces: Vec<Piece>,
     /// 方块是否已使用的标记
     pub used_pieces: Vec<bool>,
     /// 障碍块的位置和ID (row, col, piece_id)
     pub obstacle_positions: Vec<(usize, usize, u8)>,
+    /// 固定提示方块（已放在棋盘上且标记为已使用，玩家不能移动）
+    #[serde(default)]
+    pub clues: Vec<Placement>,
 }
 
 impl GameState {
@@ -307,27 +340,140 @@ impl GameState {
             pieces,
             used_pieces,
             obstacle_positions: Vec::new(),
+            clues: Vec::new(),
+        }
+    }
+
+    /// 把托盘中的方块作为提示固定在棋盘上，放不下或已使用时返回false
+    pub fn add_clue(&mut self, placement: Placement) -> bool {
+        let Some(idx) = self.pieces.iter().position(|p| p.id == placement.piece_id) else {
+            return false;
+        };
+        if self.used_pieces[idx] {
+            return false;
+        }
+
+        let mut piece = self.pieces[idx].clone();
+        piece.reset();
+        if placement.rotated {
+            piece.rotate();
+        }
+        if !self.board.can_place(&piece, placement.row, placement.col) {
+            return false;
+        }
+
+        self.board.place(&piece, placement.row, placement.col);
+        self.used_pieces[idx] = true;
+        self.clues.push(placement);
+        true
+    }
+
+    /// 托盘中未使用的方块按种类分组计数
+    pub fn inventory(&self) -> Vec<(Piece, usize)> {
+        let mut kinds: Vec<(Piece, usize)> = Vec::new();
+        for (piece, _) in self.pieces.iter().zip(&self.used_pieces).filter(|(_, used)| !**used) {
+            match kinds.iter_mut().find(|(kind, _)| kind.same_kind(piece)) {
+                Some((_, count)) => *count += 1,
+                None => kinds.push((piece.clone(), 1)),
+            }
         }
+        kinds
+    }
+
+    /// 方块是否是固定提示
+    pub fn is_clue(&self, piece_id: u8) -> bool {
+        self.clues.iter().any(|c| c.piece_id == piece_id)
+    }
+
+    /// 障碍方块的放置信息（朝向由棋盘上障碍的宽度推断）
+    pub fn obstacle_placements(&self) -> Vec<Placement> {
+        self.obstacle_positions
+            .iter()
+            .map(|&(row, col, piece_id)| {
+                let width = (col..BOARD_SIZE).take_while(|&c| self.board.get(row, c) == -(piece_id as i8)).count();
+                let rotated = crate::piece::get_piece_by_id(piece_id).is_some_and(|p| p.width != p.height && p.width != width);
+                Placement { piece_id, row, col, rotated }
+            })
+            .collect()
     }
 }
 
 /// 难度等级
-/// 注意：难度只是给玩家的参考标签，所有关卡都使用相同的3个黑色障碍块（1x1, 1x2, 1x3）
+/// 难度决定预先放置多少个障碍方块、从哪些方块中选（见 [`Difficulty::obstacle_profile`]）
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Difficulty {
-    /// 简单 - 玩家参考难度
+    /// 简单 - 预放5个方块（含较大的方块），剩下的方块少
     Easy,
-    /// 中等 - 玩家参考难度
+    /// 中等 - 预放3个黑色块（1x1, 1x2, 1x3）
     Medium,
-    /// 困难 - 玩家参考难度
+    /// 困难 - 只预放2个小黑色块
     Hard,
 }
 
+impl FromStr for Difficulty {
+    type Err = ParseNameError;
+
+    /// 解析 `easy` / `medium` / `hard`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(ParseNameError { kind: "difficulty", name: s.to_string() }),
+        }
+    }
+}
+
+/// 无法识别的名称（难度、生成方式、回放顺序等）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNameError {
+    /// 名称的类别
+    pub kind: &'static str,
+    /// 无法识别的名称
+    pub name: String,
+}
+
+impl fmt::Display for ParseNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid {}: {}", self.kind, self.name)
+    }
+}
+
+impl std::error::Error for ParseNameError {}
+
+/// 障碍配置：预先放置多少个方块作为障碍，以及从哪些方块中选
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObstacleProfile {
+    /// 障碍方块数量
+    pub count: usize,
+    /// 候选方块ID
+    pub candidates: Vec<u8>,
+}
+
+impl ObstacleProfile {
+    /// 创建障碍配置
+    pub fn new(count: usize, candidates: &[u8]) -> Self {
+        Self {
+            count,
+            candidates: candidates.to_vec(),
+        }
+    }
+}
+
 impl Difficulty {
-    /// 获取障碍方块ID
-    /// 所有难度都使用相同的3个黑色块：1x1(id=1), 1x2(id=2), 1x3(id=3)
+    /// 难度对应的障碍配置
+    ///
+    /// 单独一个1x1障碍不可能有唯一解，所以困难难度至少预放2个方块。
+    pub fn obstacle_profile(&self) -> ObstacleProfile {
+        match self {
+            Difficulty::Easy => ObstacleProfile::new(5, &[1, 2, 3, 4, 5, 6, 7]),
+            Difficulty::Medium => ObstacleProfile::new(OBSTACLE_COUNT, &[1, 2, 3]),
+            Difficulty::Hard => ObstacleProfile::new(2, &[1, 2, 3]),
+        }
+    }
+
+    /// 获取候选障碍方块ID
     pub fn get_obstacle_piece_ids(&self) -> Vec<u8> {
-        // 忽略难度参数，总是返回黑色块1、2、3
-        vec![1, 2, 3]
+        self.obstacle_profile().candidates
     }
 }
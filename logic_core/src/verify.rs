@@ -0,0 +1,253 @@
+//! 解答验证模块 - 确认玩家提交的棋盘是合法解
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use crate::types::{Board, GameState, Placement, Solution};
+use crate::BOARD_SIZE;
+
+/// 验证失败原因
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VerificationError {
+    /// 棋盘上还有空格
+    BoardNotFull { empty_cells: usize },
+    /// 关卡中的障碍（或预置方块）被修改
+    ObstacleModified { row: usize, col: usize },
+    /// 棋盘上出现了关卡中没有的方块或障碍
+    UnknownPiece { piece_id: i8, row: usize, col: usize },
+    /// 某个方块没有出现在棋盘上
+    PieceMissing(u8),
+    /// 方块占据的格子不是一个完整的矩形（被拆开或多次使用）
+    PieceNotRectangle(u8),
+    /// 方块尺寸与关卡定义不符
+    WrongDimensions { piece_id: u8, width: usize, height: usize },
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::BoardNotFull { empty_cells } => {
+                write!(f, "Board has {} empty cells", empty_cells)
+            }
+            VerificationError::ObstacleModified { row, col } => {
+                write!(f, "Obstacle at ({}, {}) was modified", row, col)
+            }
+            VerificationError::UnknownPiece { piece_id, row, col } => {
+                write!(f, "Unknown piece {} at ({}, {})", piece_id, row, col)
+            }
+            VerificationError::PieceMissing(id) => write!(f, "Piece {} is not used", id),
+            VerificationError::PieceNotRectangle(id) => {
+                write!(f, "Piece {} does not form a single rectangle", id)
+            }
+            VerificationError::WrongDimensions { piece_id, width, height } => {
+                write!(f, "Piece {} has wrong dimensions {}x{}", piece_id, width, height)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+/// 验证通过的解答
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifiedSolution {
+    /// 从棋盘还原出的解（placements按方块ID排序）
+    pub solution: Solution,
+}
+
+/// 验证玩家提交的棋盘是否是关卡的合法解
+///
+/// 要求：关卡中的非空格子（障碍）保持不变；每个未使用的方块恰好出现一次，
+/// 占据一个与其尺寸（任一朝向）一致的完整矩形；棋盘填满。
+pub fn verify_solution(level: &GameState, board: &Board) -> Result<VerifiedSolution, VerificationError> {
+    // 1. 障碍不变
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            let original = level.board.get(row, col);
+            if original != 0 && board.get(row, col) != original {
+                return Err(VerificationError::ObstacleModified { row, col });
+            }
+        }
+    }
+
+    // 2. 棋盘填满
+    let empty_cells = board.count_empty();
+    if empty_cells > 0 {
+        return Err(VerificationError::BoardNotFull { empty_cells });
+    }
+
+    // 3. 新填的格子都属于关卡中剩余的方块
+    let remaining: Vec<_> = level.pieces
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| !level.used_pieces.get(*idx).copied().unwrap_or(false))
+        .map(|(_, p)| p)
+        .collect();
+
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            let cell = board.get(row, col);
+            if level.board.get(row, col) == 0
+                && (cell <= 0 || !remaining.iter().any(|p| p.id as i8 == cell))
+            {
+                return Err(VerificationError::UnknownPiece { piece_id: cell, row, col });
+            }
+        }
+    }
+
+    // 4. 每个方块恰好占据一个尺寸正确的矩形
+    let mut placements = Vec::with_capacity(remaining.len());
+    for piece in &remaining {
+        let (row, col, width, height) = match bounding_box(level, board, piece.id) {
+            Some(bbox) => bbox,
+            None => return Err(VerificationError::PieceMissing(piece.id)),
+        };
+
+        let cell_count = (row..row + height)
+            .flat_map(|r| (col..col + width).map(move |c| (r, c)))
+            .filter(|&(r, c)| board.get(r, c) == piece.id as i8)
+            .count();
+        if cell_count != width * height {
+            return Err(VerificationError::PieceNotRectangle(piece.id));
+        }
+
+        let rotated = if (width, height) == (piece.original_width, piece.original_height) {
+            false
+        } else if (width, height) == (piece.original_height, piece.original_width) {
+            true
+        } else {
+            return Err(VerificationError::WrongDimensions { piece_id: piece.id, width, height });
+        };
+
+        placements.push(Placement {
+            piece_id: piece.id,
+            row,
+            col,
+            rotated,
+        });
+    }
+
+    placements.sort_by_key(|p| p.piece_id);
+
+    Ok(VerifiedSolution {
+        solution: Solution {
+            board: board.clone(),
+            placements,
+        },
+    })
+}
+
+/// 方块新放置格子的外接矩形 (row, col, width, height)
+fn bounding_box(level: &GameState, board: &Board, piece_id: u8) -> Option<(usize, usize, usize, usize)> {
+    let mut bbox: Option<(usize, usize, usize, usize)> = None;
+
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            if level.board.get(row, col) != 0 || board.get(row, col) != piece_id as i8 {
+                continue;
+            }
+            bbox = Some(match bbox {
+                None => (row, col, row, col),
+                Some((r0, c0, r1, c1)) => (r0.min(row), c0.min(col), r1.max(row), c1.max(col)),
+            });
+        }
+    }
+
+    bbox.map(|(r0, c0, r1, c1)| (r0, c0, c1 - c0 + 1, r1 - r0 + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::get_standard_pieces;
+    use crate::solver::Solver;
+    use crate::types::SolveResult;
+
+    /// 顶行放置3个黑色障碍的关卡及其一个解
+    fn level_and_solution() -> (GameState, Solution) {
+        let mut pieces = get_standard_pieces();
+        let mut board = Board::new();
+        for col in 0..6 {
+            let id = match col {
+                0 => -1,
+                1 | 2 => -2,
+                _ => -3,
+            };
+            board.set(0, col, id);
+        }
+        pieces.drain(0..3);
+
+        let mut level = GameState::new(pieces);
+        level.board = board;
+
+        let solution = match Solver::new(1).solve(&level) {
+            SolveResult::UniqueSolution(s) => s,
+            SolveResult::MultipleSolutions(mut s) => s.remove(0),
+            SolveResult::NoSolution => panic!("Level should be solvable"),
+        };
+        (level, solution)
+    }
+
+    #[test]
+    fn test_verify_valid_solution() {
+        let (level, solution) = level_and_solution();
+        let verified = verify_solution(&level, &solution.board).unwrap();
+
+        let mut expected = solution.placements.clone();
+        expected.sort_by_key(|p| p.piece_id);
+        assert_eq!(verified.solution.placements, expected);
+    }
+
+    #[test]
+    fn test_verify_rejects_incomplete_board() {
+        let (level, solution) = level_and_solution();
+        let mut board = solution.board.clone();
+        board.set(7, 7, 0);
+
+        assert_eq!(
+            verify_solution(&level, &board).unwrap_err(),
+            VerificationError::BoardNotFull { empty_cells: 1 }
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_modified_obstacle() {
+        let (level, solution) = level_and_solution();
+        let mut board = solution.board.clone();
+        board.set(0, 0, 4);
+
+        assert_eq!(
+            verify_solution(&level, &board).unwrap_err(),
+            VerificationError::ObstacleModified { row: 0, col: 0 }
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_fake_pieces() {
+        let (level, _) = level_and_solution();
+
+        // 全部填成同一个方块ID
+        let mut board = level.board.clone();
+        for cell in board.cells_mut().iter_mut() {
+            if *cell == 0 {
+                *cell = 4;
+            }
+        }
+        assert_eq!(
+            verify_solution(&level, &board).unwrap_err(),
+            VerificationError::PieceNotRectangle(4)
+        );
+
+        // 用已被当作障碍的方块填充
+        let mut board = level.board.clone();
+        for cell in board.cells_mut().iter_mut() {
+            if *cell == 0 {
+                *cell = 1;
+            }
+        }
+        assert!(matches!(
+            verify_solution(&level, &board),
+            Err(VerificationError::UnknownPiece { piece_id: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+//! 空洞关卡模块 - 部分格子永久挖空（甜甜圈、十字形等），方块集合相应减少
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::{enumerate_layouts_on, state_from_obstacle_board, GenerationOutcome, GenerationReport, GenerationStrategy, Generator};
+use crate::piece::{get_standard_pieces, verify_area};
+use crate::session::now_ms;
+use crate::types::{Board, Difficulty, GameState, ObstacleProfile, Piece};
+use crate::{BOARD_SIZE, OBSTACLE_COUNT, VOID_CELL};
+
+/// 枚举障碍布局时最多换几次棋盘和方块集合
+const LAYOUT_ROUNDS: usize = 8;
+
+/// 空洞图案
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VoidPattern {
+    /// 中心挖掉2×2（甜甜圈）
+    Donut,
+    /// 四个角各挖掉2×2（十字形）
+    Cross,
+    /// 随机的旋转对称空洞（4或8格），剩余格子保持连通
+    Random,
+}
+
+impl Board {
+    /// 把指定格子设为空洞
+    pub fn with_voids(mut self, cells: &[(usize, usize)]) -> Self {
+        for &(row, col) in cells {
+            self.set(row, col, VOID_CELL);
+        }
+        self
+    }
+
+    /// 非空洞的格子是否连通（四方向）
+    pub fn is_connected(&self) -> bool {
+        let Some(start) = self.cells().iter().position(|&c| c != VOID_CELL) else {
+            return true;
+        };
+        let mut seen = [false; BOARD_SIZE * BOARD_SIZE];
+        let mut stack = vec![start];
+        seen[start] = true;
+        let mut reached = 0;
+
+        while let Some(idx) = stack.pop() {
+            reached += 1;
+            let (row, col) = (idx / BOARD_SIZE, idx % BOARD_SIZE);
+            let neighbors = [
+                (row > 0).then(|| idx - BOARD_SIZE),
+                (row + 1 < BOARD_SIZE).then(|| idx + BOARD_SIZE),
+                (col > 0).then(|| idx - 1),
+                (col + 1 < BOARD_SIZE).then(|| idx + 1),
+            ];
+            for next in neighbors.into_iter().flatten() {
+                if !seen[next] && self.cells()[next] != VOID_CELL {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        reached == BOARD_SIZE * BOARD_SIZE - self.count_void()
+    }
+}
+
+impl Generator {
+    /// 生成空洞关卡
+    ///
+    /// 先挖空洞，再随机去掉总面积等于空洞格数的方块，用剩下的方块填满棋盘得到完整解，
+    /// 最后按难度选障碍并验证唯一解。失败次数过多时返回None。
+    pub fn generate_with_voids(&self, difficulty: Difficulty, pattern: VoidPattern) -> Option<GameState> {
+        self.generate_with_voids_report(difficulty, pattern, GenerationStrategy::Auto).level
+    }
+
+    /// 用指定方式生成空洞关卡，同时返回统计信息
+    pub fn generate_with_voids_report(&self, difficulty: Difficulty, pattern: VoidPattern, strategy: GenerationStrategy) -> GenerationOutcome {
+        self.generate_on(difficulty, strategy, || Board::new().with_voids(&self.void_cells(pattern)))
+    }
+
+    /// 在 `make_board` 给出的带空洞棋盘上生成关卡（每次重试重新调用）
+    ///
+    /// 生成方式的选择与 `generate_with_profile` 相同：障碍很少时从完整解反推几乎得不到唯一解，
+    /// 改为在空洞棋盘上枚举障碍布局。
+    pub(crate) fn generate_on(&self, difficulty: Difficulty, strategy: GenerationStrategy, make_board: impl Fn() -> Board) -> GenerationOutcome {
+        let started_at = now_ms();
+        let profile = difficulty.obstacle_profile();
+        let mut report = GenerationReport {
+            requested: strategy,
+            ..GenerationReport::default()
+        };
+
+        let from_solution = |report: &mut GenerationReport| (0..self.max_retries).find_map(|_| self.solution_on(&profile, make_board(), report));
+        let from_layouts = |report: &mut GenerationReport| (0..LAYOUT_ROUNDS).find_map(|_| self.layouts_on(&profile, &make_board, report));
+        let level = match strategy {
+            GenerationStrategy::Auto if profile.count < OBSTACLE_COUNT => from_layouts(&mut report),
+            GenerationStrategy::Auto => from_solution(&mut report).or_else(|| from_layouts(&mut report)),
+            GenerationStrategy::FromSolution => from_solution(&mut report),
+            GenerationStrategy::FromObstacles => from_layouts(&mut report),
+        };
+        if let Some(state) = &level {
+            debug_assert!(verify_area(&state.pieces, &state.board));
+        }
+
+        report.elapsed_ms = now_ms().saturating_sub(started_at);
+        GenerationOutcome { level, report }
+    }
+
+    /// 从完整解反推的一次尝试
+    fn solution_on(&self, profile: &ObstacleProfile, board: Board, report: &mut GenerationReport) -> Option<GameState> {
+        report.attempts += 1;
+        let Some(pieces) = self.pieces_for_area(board.count_empty(), profile) else {
+            report.rejections.placement_failed += 1;
+            return None;
+        };
+        let Some(solution) = self.complete_solution(board, pieces) else {
+            report.rejections.placement_failed += 1;
+            return None;
+        };
+
+        let obstacle_ids = self.select_obstacle_pieces(&solution, profile);
+        if obstacle_ids.len() != profile.count {
+            report.rejections.placement_failed += 1;
+            return None;
+        }
+        let state = self.create_state_from_solution(&solution, &obstacle_ids);
+        if !self.constraints.accepts_obstacles(&state.board) || !self.constraints.accepts_solution(&solution.board) {
+            report.rejections.constraints_failed += 1;
+            return None;
+        }
+
+        self.validate_unique_solution(&state, report)?;
+        report.strategy = Some(GenerationStrategy::FromSolution);
+        Some(state)
+    }
+
+    /// 在一个空洞棋盘上按随机顺序检查所选障碍方块的布局（一轮）
+    ///
+    /// 障碍很少时检查全部布局，否则和随机摆放一样最多检查 `max_retries` 个。
+    fn layouts_on(&self, profile: &ObstacleProfile, make_board: &impl Fn() -> Board, report: &mut GenerationReport) -> Option<GameState> {
+        // 方块集合必须能铺满棋盘，否则所有布局都无解
+        let tileable = (0..self.max_retries).find_map(|_| {
+            let board = make_board();
+            let pieces = self.pieces_for_area(board.count_empty(), profile)?;
+            self.complete_solution(board.clone(), pieces.clone()).map(|_| (board, pieces))
+        });
+        let Some((board, pieces)) = tileable else {
+            report.attempts += 1;
+            report.rejections.placement_failed += 1;
+            return None;
+        };
+        let candidates: Vec<&Piece> = pieces.iter().filter(|p| profile.candidates.contains(&p.id)).collect();
+        let obstacles: Vec<Piece> = candidates
+            .choose_multiple(&mut *self.rng.borrow_mut(), profile.count)
+            .map(|&p| p.clone())
+            .collect();
+        let remaining: Vec<Piece> = pieces.iter().filter(|p| !obstacles.iter().any(|o| o.id == p.id)).cloned().collect();
+
+        // 障碍多时布局数量爆炸，改为随机摆放
+        let layouts: Vec<Option<Board>> = if profile.count < OBSTACLE_COUNT {
+            let mut layouts = enumerate_layouts_on(&board, &obstacles);
+            layouts.shuffle(&mut *self.rng.borrow_mut());
+            layouts.into_iter().map(Some).collect()
+        } else {
+            (0..self.max_retries).map(|_| self.random_layout_on(&board, &obstacles)).collect()
+        };
+
+        layouts.into_iter().find_map(|layout| {
+            report.attempts += 1;
+            let Some(layout) = layout else {
+                report.rejections.placement_failed += 1;
+                return None;
+            };
+            if !self.constraints.accepts_obstacles(&layout) {
+                report.rejections.constraints_failed += 1;
+                return None;
+            }
+            let mut state = GameState::new(remaining.clone());
+            state.obstacle_positions = state_from_obstacle_board(layout.clone()).obstacle_positions;
+            state.board = layout;
+            self.accept_unique(state, report)
+        })
+    }
+
+    /// 每个障碍方块在所有能放的位置中随机选一个，某个方块放不下时返回None
+    fn random_layout_on(&self, board: &Board, obstacles: &[Piece]) -> Option<Board> {
+        let mut board = board.clone();
+        let mut rng = self.rng.borrow_mut();
+        for obstacle in obstacles {
+            let mut piece = obstacle.clone();
+            let mut positions = Vec::new();
+            for _ in 0..if piece.width == piece.height { 1 } else { 2 } {
+                for row in 0..BOARD_SIZE {
+                    for col in 0..BOARD_SIZE {
+                        if board.can_place(&piece, row, col) {
+                            positions.push((piece.width, piece.height, row, col));
+                        }
+                    }
+                }
+                piece.rotate();
+            }
+
+            let &(width, height, row, col) = positions.choose(&mut *rng)?;
+            for r in row..row + height {
+                for c in col..col + width {
+                    board.set(r, c, -(piece.id as i8));
+                }
+            }
+        }
+        Some(board)
+    }
+
+    /// 图案对应的空洞格子
+    fn void_cells(&self, pattern: VoidPattern) -> Vec<(usize, usize)> {
+        let last = BOARD_SIZE - 1;
+        match pattern {
+            VoidPattern::Donut => vec![(3, 3), (3, 4), (4, 3), (4, 4)],
+            VoidPattern::Cross => [(0, 0), (0, last - 1), (last - 1, 0), (last - 1, last - 1)]
+                .into_iter()
+                .flat_map(|(row, col)| [(row, col), (row, col + 1), (row + 1, col), (row + 1, col + 1)])
+                .collect(),
+            VoidPattern::Random => loop {
+                // 在左上象限选1~2格，旋转四次得到对称图案
+                let mut rng = self.rng.borrow_mut();
+                let count = rng.random_range(1..=2);
+                let mut cells = Vec::new();
+                for _ in 0..count {
+                    let (row, col) = (rng.random_range(0..BOARD_SIZE / 2), rng.random_range(0..BOARD_SIZE / 2));
+                    for (r, c) in [(row, col), (col, last - row), (last - row, last - col), (last - col, row)] {
+                        if !cells.contains(&(r, c)) {
+                            cells.push((r, c));
+                        }
+                    }
+                }
+                drop(rng);
+                if Board::new().with_voids(&cells).is_connected() {
+                    break cells;
+                }
+            },
+        }
+    }
+
+    /// 随机去掉一些方块，使剩下方块的总面积等于 `free_area`，且至少保留配置数量的障碍候选
+    fn pieces_for_area(&self, free_area: usize, profile: &ObstacleProfile) -> Option<Vec<Piece>> {
+        let pieces = get_standard_pieces();
+        let void_area: usize = pieces.iter().map(|p| p.area()).sum::<usize>().checked_sub(free_area)?;
+
+        // 方块只有11个，直接枚举所有子集
+        let removable: Vec<u32> = (0..1u32 << pieces.len())
+            .filter(|mask| {
+                let area: usize = pieces.iter().enumerate().filter(|(i, _)| mask & (1 << i) != 0).map(|(_, p)| p.area()).sum();
+                let kept = pieces.iter().enumerate().filter(|(i, p)| mask & (1 << i) == 0 && profile.candidates.contains(&p.id)).count();
+                area == void_area && kept >= profile.count
+            })
+            .collect();
+        let &mask = removable.choose(&mut *self.rng.borrow_mut())?;
+
+        Some(pieces.into_iter().enumerate().filter(|(i, _)| mask & (1 << i) == 0).map(|(_, p)| p).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+
+    #[test]
+    fn test_generate_void_levels() {
+        let generator = Generator::with_seed(2);
+        for pattern in [VoidPattern::Donut, VoidPattern::Cross, VoidPattern::Random] {
+            let level = generator.generate_with_voids(Difficulty::Medium, pattern).expect("Void level should generate");
+
+            assert!(level.board.count_void() >= 4);
+            assert!(level.board.is_connected());
+            assert!(verify_area(&level.pieces, &level.board));
+            assert!(level.pieces.len() < 11 - level.obstacle_positions.len());
+            assert_eq!(Solver::new(2).count_solutions(&level), 1);
+        }
+    }
+
+    #[test]
+    fn test_hard_void_levels_enumerate_layouts() {
+        // 困难只有2个障碍，反推几乎得不到唯一解，要靠枚举障碍布局
+        for seed in [1, 2, 4, 6, 7] {
+            let generator = Generator::with_seed(seed);
+            let outcome = generator.generate_with_voids_report(Difficulty::Hard, VoidPattern::Donut, GenerationStrategy::Auto);
+            let level = outcome.level.expect("Hard void level should generate");
+
+            assert_eq!(outcome.report.strategy, Some(GenerationStrategy::FromObstacles));
+            assert!(outcome.report.attempts > outcome.report.rejections.total());
+            assert_eq!(level.obstacle_positions.len(), 2);
+            assert_eq!(level.board.count_void(), 4);
+            assert!(verify_area(&level.pieces, &level.board));
+            assert_eq!(Solver::new(2).count_solutions(&level), 1);
+        }
+    }
+
+    #[test]
+    fn test_donut_text_round_trip() {
+        let board = Board::new().with_voids(&[(3, 3), (3, 4), (4, 3), (4, 4)]);
+        let text = board.to_string();
+        assert!(text.contains("× ×"));
+        assert_eq!(text.parse::<Board>().unwrap(), board);
+        assert!(!Board::new().with_voids(&[(0, 1), (1, 0)]).is_connected());
+    }
+}
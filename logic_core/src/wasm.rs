@@ -1,12 +1,47 @@
 //! WASM绑定层 - 导出API供JavaScript调用
 
+use std::str::FromStr;
+
+use rand::Rng;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
 use serde_wasm_bindgen::{from_value, to_value};
 use crate::{
-    Difficulty, GameState, Generator, Solver, piece::get_standard_pieces,
-    Board, Piece, SolveResult,
+    Difficulty, GameState, GenerationJob, GenerationStrategy, Generator, Solver, piece::get_standard_pieces,
+    Board, ExportOptions, LevelPack, ParseNameError, Piece, Replay, Session, Solution, SolveResult, verify_solution,
+    export::{puzzle_sheet_svg, puzzle_to_svg, solution_to_svg},
 };
 
+/// 解析难度、生成方式、回放顺序等名称
+fn parse_name<T: FromStr<Err = ParseNameError>>(name: &str) -> Result<T, JsValue> {
+    name.parse().map_err(|e: ParseNameError| JsValue::from_str(&e.to_string()))
+}
+
+/// 异步生成时每片尝试的候选关卡数（每个候选需要一次唯一性求解，通常只要几毫秒）
+const ASYNC_ATTEMPTS_PER_SLICE: usize = 2;
+
+#[wasm_bindgen]
+extern "C" {
+    /// 全局的setTimeout（页面和Web Worker中都可用）
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(callback: &js_sys::Function, delay_ms: i32) -> JsValue;
+}
+
+/// 让出主线程，等下一轮事件循环再继续
+async fn yield_to_event_loop() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        set_timeout(&resolve, 0);
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// 派生一个独立的生成器（随机数取自原生成器，沿用其约束），供异步任务独占
+fn fork_generator(generator: &Generator) -> Generator {
+    let mut forked = Generator::with_seed(generator.rng.borrow_mut().random());
+    forked.constraints = generator.constraints.clone();
+    forked
+}
+
 /// 初始化WASM模块
 #[wasm_bindgen(start)]
 pub fn init() {
@@ -33,19 +68,48 @@ impl WasmPuzzle {
     /// 生成新关卡
     #[wasm_bindgen(js_name = newLevel)]
     pub fn new_level(&self, difficulty: &str) -> Result<JsValue, JsValue> {
-        let diff = match difficulty {
-            "easy" => Difficulty::Easy,
-            "medium" => Difficulty::Medium,
-            "hard" => Difficulty::Hard,
-            _ => return Err(JsValue::from_str("Invalid difficulty")),
-        };
-
-        match self.generator.generate(diff) {
+        match self.generator.generate(parse_name(difficulty)?) {
             Some(state) => to_value(&state).map_err(|e| JsValue::from_str(&e.to_string())),
             None => Err(JsValue::from_str("Failed to generate level")),
         }
     }
 
+    /// 用指定方式生成关卡，返回 `{ level, report }`（失败时level为null）
+    #[wasm_bindgen(js_name = newLevelWithReport)]
+    pub fn new_level_with_report(&self, difficulty: &str, strategy: &str) -> Result<JsValue, JsValue> {
+        let outcome = self.generator.generate_with_report(parse_name(difficulty)?, parse_name(strategy)?);
+        to_value(&outcome).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 异步生成新关卡，返回Promise；生成过程分片执行，每片之间让出主线程
+    #[wasm_bindgen(js_name = newLevelAsync)]
+    pub fn new_level_async(&self, difficulty: &str) -> js_sys::Promise {
+        let diff = match parse_name::<Difficulty>(difficulty) {
+            Ok(diff) => diff,
+            Err(e) => return js_sys::Promise::reject(&e),
+        };
+        let generator = fork_generator(&self.generator);
+        let mut job = generator.start_job(&diff.obstacle_profile(), GenerationStrategy::Auto);
+
+        future_to_promise(async move {
+            while !generator.advance(&mut job, ASYNC_ATTEMPTS_PER_SLICE) {
+                yield_to_event_loop().await;
+            }
+            match job.into_outcome().level {
+                Some(state) => to_value(&state).map_err(|e| JsValue::from_str(&e.to_string())),
+                None => Err(JsValue::from_str("Failed to generate level")),
+            }
+        })
+    }
+
+    /// 开始分步生成关卡，由调用方反复调用 `step` 推进（例如在requestAnimationFrame中）
+    #[wasm_bindgen(js_name = startLevel)]
+    pub fn start_level(&self, difficulty: &str, strategy: &str) -> Result<WasmGenerationJob, JsValue> {
+        let generator = fork_generator(&self.generator);
+        let job = generator.start_job(&parse_name::<Difficulty>(difficulty)?.obstacle_profile(), parse_name(strategy)?);
+        Ok(WasmGenerationJob { generator, job })
+    }
+
     /// 求解关卡
     #[wasm_bindgen(js_name = solveLevel)]
     pub fn solve_level(&self, state_js: JsValue) -> Result<JsValue, JsValue> {
@@ -159,6 +223,7 @@ impl WasmPuzzle {
             pieces: remaining_pieces.clone(),
             used_pieces: vec![false; remaining_pieces.len()],
             obstacle_positions: Vec::new(),
+            clues: Vec::new(),
         };
 
         // 验证求解
@@ -192,4 +257,235 @@ impl WasmPuzzle {
 
         to_value(&validation).map_err(|e| JsValue::from_str(&e.to_string()))
     }
+
+    /// 验证玩家提交的棋盘是否是关卡的合法解
+    #[wasm_bindgen(js_name = verifySolution)]
+    pub fn verify_solution(&self, level_js: JsValue, board_cells: Vec<i8>) -> Result<JsValue, JsValue> {
+        let level: GameState = from_value(level_js)
+            .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+
+        let cells: [i8; 64] = board_cells
+            .try_into()
+            .map_err(|_| JsValue::from_str("Invalid board size"))?;
+
+        let verified = verify_solution(&level, &Board::from_array(cells))
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        to_value(&verified).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 把解转换为回放时间线
+    #[wasm_bindgen(js_name = replaySolution)]
+    pub fn replay_solution(
+        &self,
+        level_js: JsValue,
+        solution_js: JsValue,
+        order: &str,
+        step_ms: u32,
+    ) -> Result<JsValue, JsValue> {
+        let level: GameState = from_value(level_js)
+            .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+        let solution: Solution = from_value(solution_js)
+            .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+
+        let replay = Replay::from_solution(&level, &solution, parse_name(order)?, step_ms as u64);
+        to_value(&replay).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 生成关卡包（难度递增，关卡互不对称）
+    #[wasm_bindgen(js_name = generateLevelPack)]
+    pub fn generate_level_pack(&self, name: &str, count: usize) -> Result<JsValue, JsValue> {
+        match self.generator.generate_pack(name, count) {
+            Some(pack) => to_value(&pack).map_err(|e| JsValue::from_str(&e.to_string())),
+            None => Err(JsValue::from_str("Failed to generate level pack")),
+        }
+    }
+
+    /// 从JSON加载关卡包
+    #[wasm_bindgen(js_name = loadLevelPack)]
+    pub fn load_level_pack(&self, json: &str) -> Result<JsValue, JsValue> {
+        let pack = LevelPack::from_json(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        to_value(&pack).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 列出关卡包中的关卡（含解锁状态和最佳用时）
+    #[wasm_bindgen(js_name = listPackLevels)]
+    pub fn list_pack_levels(&self, pack_js: JsValue) -> Result<JsValue, JsValue> {
+        let pack: LevelPack = from_value(pack_js)
+            .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+        to_value(&pack.summaries()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 记录关卡包中一关的完成用时，返回更新后的关卡包
+    #[wasm_bindgen(js_name = recordPackCompletion)]
+    pub fn record_pack_completion(&self, pack_js: JsValue, index: usize, time_ms: u32) -> Result<JsValue, JsValue> {
+        let mut pack: LevelPack = from_value(pack_js)
+            .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+        pack.record_completion(index, time_ms as u64);
+        to_value(&pack).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 把关卡题面导出为SVG（传入solution时导出答案）
+    #[wasm_bindgen(js_name = exportSvg)]
+    pub fn export_svg(&self, level_js: JsValue, solution_js: JsValue, labels: bool) -> Result<String, JsValue> {
+        let options = ExportOptions { labels, ..ExportOptions::default() };
+        if solution_js.is_undefined() || solution_js.is_null() {
+            let level: GameState = from_value(level_js)
+                .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+            return Ok(puzzle_to_svg(&level, &options));
+        }
+
+        let solution: Solution = from_value(solution_js)
+            .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+        Ok(solution_to_svg(&solution, &options))
+    }
+
+    /// 导出可打印的A4题目页（SVG，单位毫米）
+    #[wasm_bindgen(js_name = exportPuzzleSheet)]
+    pub fn export_puzzle_sheet(&self, level_js: JsValue, title: &str) -> Result<String, JsValue> {
+        let level: GameState = from_value(level_js)
+            .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+        Ok(puzzle_sheet_svg(&level, title, &ExportOptions { cell_size: 12, ..ExportOptions::default() }))
+    }
+}
+
+/// 无状态的生成入口，适合在Web Worker中调用，返回 `{ level, report }`
+///
+/// 传入seed时结果可复现（同一种子在主线程和Worker中得到同一关卡）。
+#[wasm_bindgen(js_name = generateLevel)]
+pub fn generate_level(difficulty: &str, strategy: &str, seed: Option<u32>) -> Result<JsValue, JsValue> {
+    let generator = match seed {
+        Some(seed) => Generator::with_seed(seed as u64),
+        None => Generator::new(),
+    };
+    let outcome = generator.generate_with_report(parse_name(difficulty)?, parse_name(strategy)?);
+    to_value(&outcome).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// 分步执行的关卡生成任务
+#[wasm_bindgen]
+pub struct WasmGenerationJob {
+    generator: Generator,
+    job: GenerationJob,
+}
+
+#[wasm_bindgen]
+impl WasmGenerationJob {
+    /// 最多尝试 `max_attempts` 个候选关卡，任务结束时返回true
+    pub fn step(&mut self, max_attempts: usize) -> bool {
+        self.generator.advance(&mut self.job, max_attempts)
+    }
+
+    /// 任务是否已结束
+    #[wasm_bindgen(getter, js_name = isFinished)]
+    pub fn is_finished(&self) -> bool {
+        self.job.is_finished()
+    }
+
+    /// 已尝试的候选关卡数
+    #[wasm_bindgen(getter)]
+    pub fn attempts(&self) -> usize {
+        self.job.report().attempts
+    }
+
+    /// 生成结果 `{ level, report }`（未结束时level为null）
+    pub fn outcome(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.job.clone().into_outcome()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+/// WASM游戏会话（有状态，规则在Rust侧校验）
+#[wasm_bindgen]
+pub struct WasmSession {
+    session: Session,
+}
+
+#[wasm_bindgen]
+impl WasmSession {
+    /// 从关卡创建会话
+    #[wasm_bindgen(constructor)]
+    pub fn new(level_js: JsValue) -> Result<WasmSession, JsValue> {
+        let level: GameState = from_value(level_js)
+            .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+
+        Ok(Self {
+            session: Session::new(level),
+        })
+    }
+
+    /// 放置方块（使用托盘中的朝向），返回放置信息
+    pub fn place(&mut self, piece_id: u8, row: usize, col: usize) -> Result<JsValue, JsValue> {
+        let placement = self.session
+            .place(piece_id, row, col)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        to_value(&placement).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 移除方块，返回原放置信息
+    pub fn remove(&mut self, piece_id: u8) -> Result<JsValue, JsValue> {
+        let placement = self.session
+            .remove(piece_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        to_value(&placement).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 移除覆盖指定格子的方块
+    #[wasm_bindgen(js_name = removeAt)]
+    pub fn remove_at(&mut self, row: usize, col: usize) -> Result<JsValue, JsValue> {
+        let placement = self.session
+            .remove_at(row, col)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        to_value(&placement).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 旋转托盘中的方块，返回旋转后的状态
+    #[wasm_bindgen(js_name = rotateInTray)]
+    pub fn rotate_in_tray(&mut self, piece_id: u8) -> Result<bool, JsValue> {
+        self.session
+            .rotate_in_tray(piece_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 提示下一个应放置的方块（当前棋盘无法完成时返回null）
+    pub fn hint(&mut self) -> Result<JsValue, JsValue> {
+        to_value(&self.session.hint()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 是否已完成
+    #[wasm_bindgen(js_name = isSolved)]
+    pub fn is_solved(&self) -> bool {
+        self.session.is_solved()
+    }
+
+    /// 当前进度
+    pub fn progress(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.session.progress()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 当前状态快照（GameState格式）
+    pub fn state(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.session.state()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 操作历史
+    pub fn history(&self) -> Result<JsValue, JsValue> {
+        to_value(self.session.history()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 验证当前棋盘，返回还原出的解
+    pub fn verify(&self) -> Result<JsValue, JsValue> {
+        let verified = self.session
+            .verify()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        to_value(&verified).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 用操作历史生成回放
+    pub fn replay(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.session.replay()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// 重新开始
+    pub fn restart(&mut self) {
+        self.session.restart();
+    }
 }
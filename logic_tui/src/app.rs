@@ -0,0 +1,262 @@
+//! 终端游戏状态与按键处理
+
+use logic_core::piece::get_piece_by_id;
+use logic_core::{Color, Difficulty, GameState, Generator, Piece, Placement, Session, BOARD_SIZE};
+
+/// 玩家操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// 移动光标
+    MoveCursor(isize, isize),
+    /// 选择上一个/下一个方块
+    SelectPrev,
+    SelectNext,
+    /// 旋转选中的方块
+    Rotate,
+    /// 把选中的方块放到光标处
+    Place,
+    /// 移除光标处的方块
+    Remove,
+    /// 请求提示
+    Hint,
+    /// 重新开始本关
+    Restart,
+    /// 生成新关卡
+    NewLevel(Difficulty),
+    /// 退出
+    Quit,
+}
+
+/// 终端游戏状态
+pub struct App {
+    generator: Generator,
+    /// 当前难度
+    pub difficulty: Difficulty,
+    /// 当前会话
+    pub session: Session,
+    /// 光标位置 (row, col)
+    pub cursor: (usize, usize),
+    /// 选中的方块（托盘中的下标）
+    pub selected: usize,
+    /// 最近一次提示
+    pub hint: Option<Placement>,
+    /// 状态栏消息
+    pub message: String,
+    /// 是否退出
+    pub should_quit: bool,
+}
+
+impl App {
+    /// 用生成器创建新游戏
+    pub fn new(generator: Generator, difficulty: Difficulty) -> Result<Self, String> {
+        let level = generator
+            .generate(difficulty)
+            .ok_or_else(|| "Failed to generate level".to_string())?;
+        Ok(Self::with_level(generator, difficulty, level))
+    }
+
+    /// 用指定关卡创建新游戏
+    pub fn with_level(generator: Generator, difficulty: Difficulty, level: GameState) -> Self {
+        Self {
+            generator,
+            difficulty,
+            session: Session::new(level),
+            cursor: (0, 0),
+            selected: 0,
+            hint: None,
+            message: String::from("选择方块并放置到棋盘上"),
+            should_quit: false,
+        }
+    }
+
+    /// 选中的方块
+    pub fn selected_piece(&self) -> Option<&Piece> {
+        self.session.pieces().get(self.selected)
+    }
+
+    /// 选中的方块是否已在棋盘上
+    pub fn is_placed(&self, piece_id: u8) -> bool {
+        self.session.placements().iter().any(|p| p.piece_id == piece_id)
+    }
+
+    /// 处理一次操作
+    pub fn apply(&mut self, action: Action) {
+        match action {
+            Action::MoveCursor(dr, dc) => {
+                let clamp = |v: usize, d: isize| (v as isize + d).clamp(0, BOARD_SIZE as isize - 1) as usize;
+                self.cursor = (clamp(self.cursor.0, dr), clamp(self.cursor.1, dc));
+            }
+            Action::SelectPrev => self.cycle_selection(-1),
+            Action::SelectNext => self.cycle_selection(1),
+            Action::Rotate => {
+                let Some(id) = self.selected_piece().map(|p| p.id) else { return };
+                self.message = match self.session.rotate_in_tray(id) {
+                    Ok(_) => format!("旋转方块 {}", id),
+                    Err(e) => e.to_string(),
+                };
+            }
+            Action::Place => {
+                let Some(id) = self.selected_piece().map(|p| p.id) else { return };
+                let (row, col) = self.cursor;
+                match self.session.place(id, row, col) {
+                    Ok(_) => {
+                        self.hint = None;
+                        self.message = if self.session.is_solved() {
+                            format!("完成！用时 {:.1} 秒", self.session.elapsed_ms() as f64 / 1000.0)
+                        } else {
+                            format!("放置方块 {}", id)
+                        };
+                        self.cycle_selection(1);
+                    }
+                    Err(e) => self.message = e.to_string(),
+                }
+            }
+            Action::Remove => {
+                let (row, col) = self.cursor;
+                self.message = match self.session.remove_at(row, col) {
+                    Ok(p) => {
+                        self.selected = self.index_of(p.piece_id).unwrap_or(self.selected);
+                        format!("移除方块 {}", p.piece_id)
+                    }
+                    Err(e) => e.to_string(),
+                };
+            }
+            Action::Hint => {
+                self.hint = self.session.hint();
+                self.message = match &self.hint {
+                    Some(p) => {
+                        self.selected = self.index_of(p.piece_id).unwrap_or(self.selected);
+                        format!("提示：方块 {} 放在 ({}, {}){}", p.piece_id, p.row, p.col, if p.rotated { "，需要旋转" } else { "" })
+                    }
+                    None => "当前棋盘无法完成，请先移除一些方块".to_string(),
+                };
+            }
+            Action::Restart => {
+                self.session.restart();
+                self.hint = None;
+                self.selected = 0;
+                self.message = "重新开始".to_string();
+            }
+            Action::NewLevel(difficulty) => match self.generator.generate(difficulty) {
+                Some(level) => {
+                    self.difficulty = difficulty;
+                    self.session = Session::new(level);
+                    self.hint = None;
+                    self.selected = 0;
+                    self.message = "新关卡".to_string();
+                }
+                None => self.message = "生成关卡失败".to_string(),
+            },
+            Action::Quit => self.should_quit = true,
+        }
+    }
+
+    /// 格子的颜色（空格为None）
+    pub fn cell_color(&self, row: usize, col: usize) -> Option<Color> {
+        let id = self.session.board().get(row, col).unsigned_abs();
+        if id == 0 {
+            return None;
+        }
+        self.session
+            .pieces()
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.color)
+            .or_else(|| get_piece_by_id(id).map(|p| p.color))
+    }
+
+    /// 格子是否是空洞
+    pub fn is_void(&self, row: usize, col: usize) -> bool {
+        self.session.board().is_void(row, col)
+    }
+
+    /// 选中方块在光标处的预览格子，以及是否可以放置
+    pub fn preview(&self) -> Option<(Vec<(usize, usize)>, bool)> {
+        let piece = self.selected_piece()?;
+        if self.is_placed(piece.id) || self.session.is_solved() {
+            return None;
+        }
+
+        let (row, col) = self.cursor;
+        let cells = (row..(row + piece.height).min(BOARD_SIZE))
+            .flat_map(|r| (col..(col + piece.width).min(BOARD_SIZE)).map(move |c| (r, c)))
+            .collect();
+        Some((cells, self.session.board().can_place(piece, row, col)))
+    }
+
+    fn index_of(&self, piece_id: u8) -> Option<usize> {
+        self.session.pieces().iter().position(|p| p.id == piece_id)
+    }
+
+    /// 切换选中的方块，跳过已放置的方块
+    fn cycle_selection(&mut self, step: isize) {
+        let count = self.session.pieces().len();
+        for offset in 1..=count {
+            let idx = (self.selected as isize + step * offset as isize).rem_euclid(count as isize) as usize;
+            if !self.is_placed(self.session.pieces()[idx].id) {
+                self.selected = idx;
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        let generator = Generator::with_seed(1);
+        App::new(generator, Difficulty::Easy).unwrap()
+    }
+
+    #[test]
+    fn test_cursor_stays_on_board() {
+        let mut app = app();
+        app.apply(Action::MoveCursor(-1, -1));
+        assert_eq!(app.cursor, (0, 0));
+        for _ in 0..20 {
+            app.apply(Action::MoveCursor(1, 1));
+        }
+        assert_eq!(app.cursor, (BOARD_SIZE - 1, BOARD_SIZE - 1));
+    }
+
+    #[test]
+    fn test_play_with_hints() {
+        let mut app = app();
+
+        // 按提示一直放置直到完成
+        while !app.session.is_solved() {
+            app.apply(Action::Hint);
+            let hint = app.hint.clone().expect("Level from generator should be solvable");
+            assert_eq!(app.selected_piece().map(|p| p.id), Some(hint.piece_id));
+            if app.selected_piece().unwrap().rotated != hint.rotated {
+                app.apply(Action::Rotate);
+            }
+            app.cursor = (hint.row, hint.col);
+            app.apply(Action::Place);
+        }
+
+        assert!(app.session.verify().is_ok());
+        assert!(app.preview().is_none());
+    }
+
+    #[test]
+    fn test_remove_selects_removed_piece() {
+        let mut app = app();
+        let id = app.selected_piece().unwrap().id;
+        let (row, col) = (0..BOARD_SIZE * BOARD_SIZE)
+            .map(|i| (i / BOARD_SIZE, i % BOARD_SIZE))
+            .find(|&(r, c)| app.session.board().can_place(app.selected_piece().unwrap(), r, c))
+            .unwrap();
+
+        app.cursor = (row, col);
+        app.apply(Action::Place);
+        assert_ne!(app.selected_piece().unwrap().id, id);
+        assert_eq!(app.cell_color(row, col), app.session.pieces().iter().find(|p| p.id == id).map(|p| p.color));
+
+        app.apply(Action::Remove);
+        assert_eq!(app.selected_piece().unwrap().id, id);
+        assert_eq!(app.cell_color(row, col), None);
+    }
+}
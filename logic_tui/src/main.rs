@@ -0,0 +1,66 @@
+//! 终端版逻辑拼图 - 纯键盘操作，可通过SSH游玩和测试关卡
+
+mod app;
+mod ui;
+
+use std::time::Duration;
+
+use logic_core::{Difficulty, Generator};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+
+use app::{Action, App};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = App::new(Generator::new(), Difficulty::Medium)?;
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app);
+    ratatui::restore();
+
+    result
+}
+
+fn run(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
+    while !app.should_quit {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        // 定时刷新计时器
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(action) = key_action(key.code, key.modifiers) {
+                app.apply(action);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 按键映射
+fn key_action(code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+    let action = match code {
+        KeyCode::Up => Action::MoveCursor(-1, 0),
+        KeyCode::Down => Action::MoveCursor(1, 0),
+        KeyCode::Left => Action::MoveCursor(0, -1),
+        KeyCode::Right => Action::MoveCursor(0, 1),
+        KeyCode::Tab | KeyCode::Char(']') => Action::SelectNext,
+        KeyCode::BackTab | KeyCode::Char('[') => Action::SelectPrev,
+        KeyCode::Char('r') => Action::Rotate,
+        KeyCode::Enter | KeyCode::Char(' ') => Action::Place,
+        KeyCode::Char('x') | KeyCode::Delete | KeyCode::Backspace => Action::Remove,
+        KeyCode::Char('?') => Action::Hint,
+        KeyCode::Char('R') => Action::Restart,
+        KeyCode::Char('1') => Action::NewLevel(Difficulty::Easy),
+        KeyCode::Char('2') => Action::NewLevel(Difficulty::Medium),
+        KeyCode::Char('3') => Action::NewLevel(Difficulty::Hard),
+        KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        _ => return None,
+    };
+    Some(action)
+}
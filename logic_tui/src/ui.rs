@@ -0,0 +1,154 @@
+//! 终端界面绘制
+
+use logic_core::BOARD_SIZE;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color as TermColor, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+
+/// 方块颜色转换为终端真彩色
+fn term_color(color: logic_core::Color) -> TermColor {
+    let (r, g, b) = color.rgb();
+    TermColor::Rgb(r, g, b)
+}
+
+/// 绘制整个界面
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main, status] = Layout::vertical([Constraint::Min(12), Constraint::Length(4)]).areas(frame.area());
+    let [board_area, tray_area] =
+        Layout::horizontal([Constraint::Length(BOARD_SIZE as u16 * 2 + 2), Constraint::Min(24)]).areas(main);
+
+    draw_board(frame, app, board_area);
+    draw_tray(frame, app, tray_area);
+    draw_status(frame, app, status);
+}
+
+fn draw_board(frame: &mut Frame, app: &App, area: Rect) {
+    let preview = app.preview();
+    let hint_cells: Vec<(usize, usize)> = app
+        .hint
+        .as_ref()
+        .and_then(|h| {
+            let mut piece = app.session.pieces().iter().find(|p| p.id == h.piece_id)?.clone();
+            piece.reset();
+            if h.rotated {
+                piece.rotate();
+            }
+            Some(
+                (h.row..h.row + piece.height)
+                    .flat_map(|r| (h.col..h.col + piece.width).map(move |c| (r, c)))
+                    .collect(),
+            )
+        })
+        .unwrap_or_default();
+
+    let lines: Vec<Line> = (0..BOARD_SIZE)
+        .map(|row| {
+            let spans: Vec<Span> = (0..BOARD_SIZE)
+                .map(|col| {
+                    let mut style = Style::default();
+                    let mut text = "  ";
+
+                    if app.is_void(row, col) {
+                        text = "××";
+                        style = style.fg(TermColor::DarkGray);
+                    } else if let Some(color) = app.cell_color(row, col) {
+                        style = style.bg(term_color(color));
+                    } else if hint_cells.contains(&(row, col)) {
+                        text = "··";
+                    }
+
+                    if let Some((cells, ok)) = &preview {
+                        if cells.contains(&(row, col)) {
+                            style = style.bg(if *ok { TermColor::Green } else { TermColor::Red });
+                        }
+                    }
+
+                    if app.cursor == (row, col) {
+                        text = "[]";
+                        style = style.add_modifier(Modifier::BOLD);
+                    }
+
+                    Span::styled(text, style)
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+
+    let title = format!(" {:?} ", app.difficulty);
+    frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)), area);
+}
+
+fn draw_tray(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .session
+        .pieces()
+        .iter()
+        .enumerate()
+        .map(|(idx, piece)| {
+            let placed = app.is_placed(piece.id);
+            let marker = if idx == app.selected { "▶ " } else { "  " };
+            let mut label_style = Style::default();
+            if placed {
+                label_style = label_style.add_modifier(Modifier::DIM | Modifier::CROSSED_OUT);
+            }
+
+            ListItem::new(Line::from(vec![
+                Span::raw(marker),
+                Span::styled("  ", Style::default().bg(term_color(piece.color))),
+                Span::styled(
+                    format!(" #{:<2} {}×{}{}", piece.id, piece.width, piece.height, if piece.rotated { " ↻" } else { "" }),
+                    label_style,
+                ),
+            ]))
+        })
+        .collect();
+
+    frame.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).title(" 方块 ")), area);
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let progress = app.session.progress();
+    let lines = vec![
+        Line::from(format!(
+            "方块 {}/{}  格子 {}/{}  用时 {}s  提示 {}  {}",
+            progress.placed_pieces,
+            progress.total_pieces,
+            progress.filled_cells,
+            progress.total_cells,
+            progress.elapsed_ms / 1000,
+            progress.hints_used,
+            app.message,
+        )),
+        Line::from("方向键 移动  Tab/[ ] 选择  r 旋转  Enter 放置  x 移除  ? 提示  R 重来  1/2/3 新关卡  q 退出")
+            .style(Style::default().add_modifier(Modifier::DIM)),
+    ];
+
+    frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL)), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logic_core::{Difficulty, Generator};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn test_draw_renders_board_and_tray() {
+        let app = App::new(Generator::with_seed(3), Difficulty::Easy).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("Easy"));
+        assert!(text.contains("[]"));
+        assert!(text.contains("#11"));
+    }
+}
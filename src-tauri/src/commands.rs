@@ -1,20 +1,83 @@
 //! Tauri命令接口
 
+use std::sync::Mutex;
+
 use logic_core::{
-    Board, Difficulty, GameState, Generator, Piece, Solution, SolveResult, Solver,
+    Board, Difficulty, GameState, GenerationOutcome, GenerationStrategy, Generator, LevelPack, Piece, Placement, Replay, ReplayOrder,
+    Session, Solution, SolveResult, Solver, VerifiedSolution, verify_solution as verify_board,
+    pack::PackLevelSummary,
     piece::get_standard_pieces,
+    session::{Move, Progress},
 };
 use serde::Serialize;
+use tauri::State;
+
+use crate::profile::{self, ProfileStats, ProfileStore, SolveRecord};
+
+/// 当前游戏会话（由Tauri托管）
+#[derive(Default)]
+pub struct SessionState(Mutex<Option<ActiveSession>>);
+
+/// 会话及其完成记录是否已写入档案
+struct ActiveSession {
+    session: Session,
+    recorded: bool,
+}
+
+impl SessionState {
+    /// 在当前会话上执行操作
+    fn with<T>(&self, f: impl FnOnce(&mut Session) -> Result<T, String>) -> Result<T, String> {
+        let mut guard = self.0.lock().map_err(|_| "Session lock poisoned".to_string())?;
+        let active = guard.as_mut().ok_or_else(|| "No active session".to_string())?;
+        f(&mut active.session)
+    }
+
+    /// 替换当前会话
+    fn replace(&self, session: Session) -> Result<(), String> {
+        *self.0.lock().map_err(|_| "Session lock poisoned".to_string())? = Some(ActiveSession { session, recorded: false });
+        Ok(())
+    }
+
+    /// 把已完成的会话交给 `store` 记录，每个会话只记录一次
+    ///
+    /// 记录期间一直持有会话锁，重复调用（例如双击）只有第一次成功；`store` 失败时可以重试。
+    fn record_once<T>(&self, store: impl FnOnce(SolveRecord) -> Result<T, String>) -> Result<T, String> {
+        let mut guard = self.0.lock().map_err(|_| "Session lock poisoned".to_string())?;
+        let active = guard.as_mut().ok_or_else(|| "No active session".to_string())?;
+        if active.recorded {
+            return Err("Session already recorded".to_string());
+        }
+
+        let session = &active.session;
+        session.verify().map_err(|e| e.to_string())?;
+        let progress = session.progress();
+        let record = SolveRecord {
+            level_key: profile::level_key(session.level()),
+            difficulty: level_difficulty(session.level())?,
+            time_ms: progress.elapsed_ms,
+            hints_used: progress.hints_used,
+            moves: progress.moves,
+            finished_at_ms: profile::now_ms(),
+        };
+
+        let stored = store(record)?;
+        active.recorded = true;
+        Ok(stored)
+    }
+}
+
+/// 按障碍方块数推断关卡难度
+fn level_difficulty(level: &GameState) -> Result<Difficulty, String> {
+    [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard]
+        .into_iter()
+        .find(|difficulty| difficulty.obstacle_profile().count == level.obstacle_positions.len())
+        .ok_or_else(|| "Cannot infer difficulty from level".to_string())
+}
 
 /// 生成新关卡
 #[tauri::command]
 pub fn new_level(difficulty: String) -> Result<GameState, String> {
-    let diff = match difficulty.as_str() {
-        "easy" => Difficulty::Easy,
-        "medium" => Difficulty::Medium,
-        "hard" => Difficulty::Hard,
-        _ => return Err("Invalid difficulty".to_string()),
-    };
+    let diff = difficulty.parse::<Difficulty>().map_err(|_| "Invalid difficulty".to_string())?;
 
     let generator = Generator::new();
     generator
@@ -22,6 +85,18 @@ pub fn new_level(difficulty: String) -> Result<GameState, String> {
         .ok_or_else(|| "Failed to generate level".to_string())
 }
 
+/// 用指定方式生成关卡，返回关卡和生成统计（失败时关卡为null）
+#[tauri::command]
+pub fn new_level_with_report(difficulty: String, strategy: Option<String>) -> Result<GenerationOutcome, String> {
+    let diff = difficulty.parse::<Difficulty>().map_err(|_| "Invalid difficulty".to_string())?;
+    let strategy = match strategy {
+        Some(strategy) => strategy.parse::<GenerationStrategy>().map_err(|_| "Invalid generation strategy".to_string())?,
+        None => GenerationStrategy::default(),
+    };
+
+    Ok(Generator::new().generate_with_report(diff, strategy))
+}
+
 /// 求解响应结构体
 #[derive(Debug, Serialize)]
 pub struct SolveResponse {
@@ -163,6 +238,7 @@ pub fn validate_custom_obstacles(
         pieces: remaining_pieces.clone(),
         used_pieces: vec![false; remaining_pieces.len()],
         obstacle_positions: Vec::new(),
+        clues: Vec::new(),
     };
 
     // 使用求解器验证（找2个解来判断唯一性）
@@ -187,3 +263,214 @@ pub fn validate_custom_obstacles(
         },
     })
 }
+
+/// 验证玩家提交的棋盘是否是关卡的合法解
+#[tauri::command]
+pub fn verify_solution(level: GameState, board_cells: Vec<i8>) -> Result<VerifiedSolution, String> {
+    let cells: [i8; 64] = board_cells
+        .try_into()
+        .map_err(|_| "Invalid board size".to_string())?;
+
+    verify_board(&level, &Board::from_array(cells)).map_err(|e| e.to_string())
+}
+
+/// 把解转换为回放时间线
+#[tauri::command]
+pub fn replay_solution(
+    level: GameState,
+    solution: Solution,
+    order: String,
+    step_ms: u64,
+) -> Result<Replay, String> {
+    let order = order.parse::<ReplayOrder>().map_err(|_| "Invalid replay order".to_string())?;
+
+    Ok(Replay::from_solution(&level, &solution, order, step_ms))
+}
+
+/// 生成关卡包（难度递增，关卡互不对称）
+#[tauri::command]
+pub fn generate_level_pack(name: String, count: usize) -> Result<LevelPack, String> {
+    Generator::new()
+        .generate_pack(&name, count)
+        .ok_or_else(|| "Failed to generate level pack".to_string())
+}
+
+/// 从JSON加载关卡包
+#[tauri::command]
+pub fn load_level_pack(json: String) -> Result<LevelPack, String> {
+    LevelPack::from_json(&json).map_err(|e| e.to_string())
+}
+
+/// 列出关卡包中的关卡（含解锁状态和最佳用时）
+#[tauri::command]
+pub fn list_pack_levels(pack: LevelPack) -> Vec<PackLevelSummary> {
+    pack.summaries()
+}
+
+/// 记录关卡包中一关的完成用时，返回更新后的关卡包
+#[tauri::command]
+pub fn record_pack_completion(mut pack: LevelPack, index: usize, time_ms: u64) -> LevelPack {
+    pack.record_completion(index, time_ms);
+    pack
+}
+
+/// 开始新的游戏会话，返回初始状态
+#[tauri::command]
+pub fn start_session(level: GameState, session: State<'_, SessionState>) -> Result<GameState, String> {
+    let new_session = Session::new(level);
+    let state = new_session.state();
+    session.replace(new_session)?;
+    Ok(state)
+}
+
+/// 在会话中放置方块（使用托盘中的朝向）
+#[tauri::command]
+pub fn session_place(
+    piece_id: u8,
+    row: usize,
+    col: usize,
+    session: State<'_, SessionState>,
+) -> Result<Placement, String> {
+    session.with(|s| s.place(piece_id, row, col).map_err(|e| e.to_string()))
+}
+
+/// 从会话棋盘上移除方块
+#[tauri::command]
+pub fn session_remove(piece_id: u8, session: State<'_, SessionState>) -> Result<Placement, String> {
+    session.with(|s| s.remove(piece_id).map_err(|e| e.to_string()))
+}
+
+/// 移除覆盖指定格子的方块
+#[tauri::command]
+pub fn session_remove_at(
+    row: usize,
+    col: usize,
+    session: State<'_, SessionState>,
+) -> Result<Placement, String> {
+    session.with(|s| s.remove_at(row, col).map_err(|e| e.to_string()))
+}
+
+/// 旋转托盘中的方块，返回旋转后的状态
+#[tauri::command]
+pub fn session_rotate(piece_id: u8, session: State<'_, SessionState>) -> Result<bool, String> {
+    session.with(|s| s.rotate_in_tray(piece_id).map_err(|e| e.to_string()))
+}
+
+/// 提示下一个应放置的方块（当前棋盘无法完成时返回None）
+#[tauri::command]
+pub fn session_hint(session: State<'_, SessionState>) -> Result<Option<Placement>, String> {
+    session.with(|s| Ok(s.hint()))
+}
+
+/// 会话是否已完成
+#[tauri::command]
+pub fn session_is_solved(session: State<'_, SessionState>) -> Result<bool, String> {
+    session.with(|s| Ok(s.is_solved()))
+}
+
+/// 会话进度
+#[tauri::command]
+pub fn session_progress(session: State<'_, SessionState>) -> Result<Progress, String> {
+    session.with(|s| Ok(s.progress()))
+}
+
+/// 会话当前状态快照
+#[tauri::command]
+pub fn session_state(session: State<'_, SessionState>) -> Result<GameState, String> {
+    session.with(|s| Ok(s.state()))
+}
+
+/// 会话操作历史
+#[tauri::command]
+pub fn session_history(session: State<'_, SessionState>) -> Result<Vec<Move>, String> {
+    session.with(|s| Ok(s.history().to_vec()))
+}
+
+/// 用会话操作历史生成回放
+#[tauri::command]
+pub fn session_replay(session: State<'_, SessionState>) -> Result<Replay, String> {
+    session.with(|s| Ok(s.replay()))
+}
+
+/// 玩家档案（由Tauri托管，保存在应用数据目录）
+pub struct ProfileState(pub Mutex<ProfileStore>);
+
+/// 记录一局完成的游戏，返回更新后的统计
+#[tauri::command]
+pub fn record_solve(
+    level: GameState,
+    difficulty: String,
+    time_ms: u64,
+    hints_used: u32,
+    moves: usize,
+    profile: State<'_, ProfileState>,
+) -> Result<ProfileStats, String> {
+    let record = SolveRecord {
+        level_key: profile::level_key(&level),
+        difficulty: difficulty.parse::<Difficulty>().map_err(|_| "Invalid difficulty".to_string())?,
+        time_ms,
+        hints_used,
+        moves,
+        finished_at_ms: profile::now_ms(),
+    };
+
+    profile.0.lock().map_err(|_| "Profile lock poisoned".to_string())?.record(record)
+}
+
+/// 记录当前已完成的会话，难度、用时、提示和操作次数取自会话（每个会话只能记录一次）
+#[tauri::command]
+pub fn record_session_solve(
+    session: State<'_, SessionState>,
+    profile: State<'_, ProfileState>,
+) -> Result<ProfileStats, String> {
+    session.record_once(|record| profile.0.lock().map_err(|_| "Profile lock poisoned".to_string())?.record(record))
+}
+
+/// 查询玩家统计
+#[tauri::command]
+pub fn get_profile_stats(profile: State<'_, ProfileState>) -> Result<ProfileStats, String> {
+    let store = profile.0.lock().map_err(|_| "Profile lock poisoned".to_string())?;
+    Ok(store.profile().stats())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按唯一解摆完所有方块的会话
+    fn solved_session(difficulty: Difficulty) -> Session {
+        let level = Generator::with_seed(5).generate(difficulty).expect("level should generate");
+        let SolveResult::UniqueSolution(solution) = Solver::new(2).solve(&level) else {
+            panic!("Generated level should be unique");
+        };
+        let mut session = Session::new(level);
+        for placement in &solution.placements {
+            if placement.rotated {
+                session.rotate_in_tray(placement.piece_id).unwrap();
+            }
+            session.place(placement.piece_id, placement.row, placement.col).unwrap();
+        }
+        assert!(session.is_solved());
+        session
+    }
+
+    #[test]
+    fn test_session_solve_is_recorded_once() {
+        let state = SessionState::default();
+        state.replace(solved_session(Difficulty::Hard)).unwrap();
+
+        let mut records = Vec::new();
+        let mut store = |record: SolveRecord| {
+            records.push(record.difficulty);
+            Ok(records.len())
+        };
+        assert_eq!(state.record_once(&mut store), Ok(1));
+        assert_eq!(state.record_once(&mut store), Err("Session already recorded".to_string()));
+
+        // 写入档案失败时不算已记录
+        state.replace(solved_session(Difficulty::Medium)).unwrap();
+        assert!(state.record_once(|_| Err::<usize, _>("disk full".to_string())).is_err());
+        assert_eq!(state.record_once(&mut store), Ok(2));
+        assert_eq!(records, [Difficulty::Hard, Difficulty::Medium]);
+    }
+}
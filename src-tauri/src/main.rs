@@ -2,17 +2,50 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod commands;
+mod profile;
+
+use std::sync::Mutex;
 
 use commands::*;
+use profile::ProfileStore;
+use tauri::Manager;
 
 fn main() {
     tauri::Builder::default()
+        .manage(SessionState::default())
+        .setup(|app| {
+            let dir = app.path().app_data_dir()?;
+            let store = ProfileStore::load_or_recover(&dir);
+            app.manage(ProfileState(Mutex::new(store)));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             new_level,
+            new_level_with_report,
             solve_level,
             check_placement,
             get_pieces,
             validate_custom_obstacles,
+            verify_solution,
+            replay_solution,
+            generate_level_pack,
+            load_level_pack,
+            list_pack_levels,
+            record_pack_completion,
+            start_session,
+            session_place,
+            session_remove,
+            session_remove_at,
+            session_rotate,
+            session_hint,
+            session_is_solved,
+            session_progress,
+            session_state,
+            session_history,
+            session_replay,
+            record_solve,
+            record_session_solve,
+            get_profile_stats,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
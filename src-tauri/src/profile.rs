@@ -0,0 +1,297 @@
+//! 玩家档案 - 持久化已完成的关卡和统计数据
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logic_core::{Difficulty, GameState};
+use serde::{Deserialize, Serialize};
+
+/// 档案文件名
+pub const PROFILE_FILE: &str = "profile.json";
+
+const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// 一次完成的游戏记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolveRecord {
+    /// 关卡标识（障碍布局的对称规范形式）
+    pub level_key: String,
+    /// 难度
+    pub difficulty: Difficulty,
+    /// 用时（毫秒）
+    pub time_ms: u64,
+    /// 使用提示次数
+    pub hints_used: u32,
+    /// 操作次数
+    pub moves: usize,
+    /// 完成时间（Unix毫秒）
+    pub finished_at_ms: u64,
+}
+
+/// 单个难度的统计
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DifficultyStats {
+    /// 难度
+    pub difficulty: Difficulty,
+    /// 完成次数
+    pub solved: usize,
+    /// 总用时（毫秒）
+    pub total_time_ms: u64,
+    /// 最佳用时（毫秒）
+    pub best_time_ms: Option<u64>,
+    /// 平均用时（毫秒）
+    pub average_time_ms: Option<u64>,
+    /// 使用提示总次数
+    pub hints_used: u32,
+}
+
+/// 档案汇总统计
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileStats {
+    /// 完成总次数
+    pub total_solved: usize,
+    /// 完成过的不同关卡数
+    pub unique_levels: usize,
+    /// 总用时（毫秒）
+    pub total_time_ms: u64,
+    /// 使用提示总次数
+    pub hints_used: u32,
+    /// 当前连续天数
+    pub current_streak: u32,
+    /// 最长连续天数
+    pub best_streak: u32,
+    /// 各难度统计
+    pub per_difficulty: Vec<DifficultyStats>,
+}
+
+/// 玩家档案（保存到磁盘的内容）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    /// 所有完成记录
+    pub records: Vec<SolveRecord>,
+    /// 当前连续天数
+    pub current_streak: u32,
+    /// 最长连续天数
+    pub best_streak: u32,
+    /// 最后一次完成的日期（Unix天数）
+    pub last_solved_day: Option<u64>,
+}
+
+impl PlayerProfile {
+    /// 记录一次完成并更新连续天数
+    pub fn record(&mut self, record: SolveRecord) {
+        let day = record.finished_at_ms / MS_PER_DAY;
+        self.current_streak = match self.last_solved_day {
+            Some(last) if last == day => self.current_streak.max(1),
+            Some(last) if last + 1 == day => self.current_streak + 1,
+            _ => 1,
+        };
+        self.best_streak = self.best_streak.max(self.current_streak);
+        self.last_solved_day = Some(self.last_solved_day.map_or(day, |last| last.max(day)));
+        self.records.push(record);
+    }
+
+    /// 汇总统计
+    pub fn stats(&self) -> ProfileStats {
+        let mut unique: Vec<&str> = self.records.iter().map(|r| r.level_key.as_str()).collect();
+        unique.sort_unstable();
+        unique.dedup();
+
+        let per_difficulty = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard]
+            .into_iter()
+            .map(|difficulty| {
+                let records: Vec<&SolveRecord> =
+                    self.records.iter().filter(|r| r.difficulty == difficulty).collect();
+                let total_time_ms: u64 = records.iter().map(|r| r.time_ms).sum();
+
+                DifficultyStats {
+                    difficulty,
+                    solved: records.len(),
+                    total_time_ms,
+                    best_time_ms: records.iter().map(|r| r.time_ms).min(),
+                    average_time_ms: (!records.is_empty())
+                        .then(|| total_time_ms / records.len() as u64),
+                    hints_used: records.iter().map(|r| r.hints_used).sum(),
+                }
+            })
+            .collect();
+
+        ProfileStats {
+            total_solved: self.records.len(),
+            unique_levels: unique.len(),
+            total_time_ms: self.records.iter().map(|r| r.time_ms).sum(),
+            hints_used: self.records.iter().map(|r| r.hints_used).sum(),
+            current_streak: self.current_streak,
+            best_streak: self.best_streak,
+            per_difficulty,
+        }
+    }
+}
+
+/// 档案存储（JSON文件）
+#[derive(Debug)]
+pub struct ProfileStore {
+    path: PathBuf,
+    profile: PlayerProfile,
+}
+
+impl ProfileStore {
+    /// 从文件加载档案，文件不存在时创建空档案
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let profile = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| e.to_string())?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => PlayerProfile::default(),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        Ok(Self { path, profile })
+    }
+
+    /// 从应用数据目录加载档案
+    pub fn load_from_dir(dir: &Path) -> Result<Self, String> {
+        Self::load(dir.join(PROFILE_FILE))
+    }
+
+    /// 从应用数据目录加载档案；档案损坏时改名为 `profile.json.bak` 并从空档案开始，不影响应用启动
+    pub fn load_or_recover(dir: &Path) -> Self {
+        let path = dir.join(PROFILE_FILE);
+        match Self::load(&path) {
+            Ok(store) => store,
+            Err(e) => {
+                let backup = path.with_extension("json.bak");
+                eprintln!("Cannot load {}: {}; moving it to {}", path.display(), e, backup.display());
+                if let Err(e) = fs::rename(&path, &backup) {
+                    eprintln!("Cannot back up {}: {}", path.display(), e);
+                }
+                Self { path, profile: PlayerProfile::default() }
+            }
+        }
+    }
+
+    /// 档案内容
+    pub fn profile(&self) -> &PlayerProfile {
+        &self.profile
+    }
+
+    /// 记录一次完成并写回磁盘
+    pub fn record(&mut self, record: SolveRecord) -> Result<ProfileStats, String> {
+        self.profile.record(record);
+        self.save()?;
+        Ok(self.profile.stats())
+    }
+
+    /// 写回磁盘（先写临时文件再替换，避免写坏档案）
+    pub fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+
+        let json = serde_json::to_string_pretty(&self.profile).map_err(|e| e.to_string())?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, &self.path).map_err(|e| e.to_string())
+    }
+}
+
+/// 关卡标识：障碍布局对称规范形式的十六进制编码
+pub fn level_key(level: &GameState) -> String {
+    level
+        .board
+        .canonical()
+        .cells()
+        .iter()
+        .map(|&cell| format!("{:02x}", cell.min(0) as u8))
+        .collect()
+}
+
+/// 当前Unix毫秒
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(key: &str, difficulty: Difficulty, time_ms: u64, day: u64) -> SolveRecord {
+        SolveRecord {
+            level_key: key.to_string(),
+            difficulty,
+            time_ms,
+            hints_used: 1,
+            moves: 10,
+            finished_at_ms: day * MS_PER_DAY + 1000,
+        }
+    }
+
+    #[test]
+    fn test_stats_and_streaks() {
+        let mut profile = PlayerProfile::default();
+        profile.record(record("a", Difficulty::Easy, 3000, 100));
+        profile.record(record("b", Difficulty::Easy, 1000, 100));
+        profile.record(record("a", Difficulty::Hard, 9000, 101));
+        profile.record(record("c", Difficulty::Medium, 5000, 102));
+
+        let stats = profile.stats();
+        assert_eq!(stats.total_solved, 4);
+        assert_eq!(stats.unique_levels, 3);
+        assert_eq!(stats.hints_used, 4);
+        assert_eq!(stats.current_streak, 3);
+        assert_eq!(stats.best_streak, 3);
+
+        let easy = &stats.per_difficulty[0];
+        assert_eq!(easy.solved, 2);
+        assert_eq!(easy.best_time_ms, Some(1000));
+        assert_eq!(easy.average_time_ms, Some(2000));
+
+        // 中断一天后重新计数
+        profile.record(record("d", Difficulty::Easy, 1000, 104));
+        assert_eq!(profile.current_streak, 1);
+        assert_eq!(profile.best_streak, 3);
+    }
+
+    #[test]
+    fn test_store_persists_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut store = ProfileStore::load_from_dir(dir.path()).unwrap();
+        assert!(store.profile().records.is_empty());
+        store.record(record("a", Difficulty::Medium, 4000, 10)).unwrap();
+        store.record(record("b", Difficulty::Medium, 2000, 11)).unwrap();
+
+        let reloaded = ProfileStore::load_from_dir(dir.path()).unwrap();
+        assert_eq!(reloaded.profile().records.len(), 2);
+        let stats = reloaded.profile().stats();
+        assert_eq!(stats.per_difficulty[1].best_time_ms, Some(2000));
+        assert_eq!(stats.current_streak, 2);
+    }
+
+    #[test]
+    fn test_load_rejects_corrupt_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(PROFILE_FILE), "not json").unwrap();
+
+        assert!(ProfileStore::load_from_dir(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_file_is_backed_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PROFILE_FILE);
+        fs::write(&path, "\u{0}garbage{{").unwrap();
+
+        let mut store = ProfileStore::load_or_recover(dir.path());
+        assert!(store.profile().records.is_empty());
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(path.with_extension("json.bak")).unwrap(), "\u{0}garbage{{");
+
+        // 恢复后的档案可以正常写回
+        store.record(record("a", Difficulty::Easy, 1000, 1)).unwrap();
+        assert_eq!(ProfileStore::load_from_dir(dir.path()).unwrap().profile().records.len(), 1);
+    }
+}
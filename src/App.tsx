@@ -90,6 +90,7 @@ function App() {
       pieces: allPieces,
       used_pieces: Array(11).fill(false),
       obstacle_positions: [],
+      clues: [],
     });
 
     selectPiece(null);
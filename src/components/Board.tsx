@@ -1,5 +1,5 @@
 import { useState } from 'react';
-import { COLOR_MAP, PIECE_ID_TO_COLOR } from '../types/game';
+import { COLOR_MAP, PIECE_ID_TO_COLOR, VOID_CELL } from '../types/game';
 
 interface BoardProps {
   cells: number[];
@@ -11,7 +11,10 @@ interface BoardProps {
 export function Board({ cells, onCellClick, onCellRightClick }: BoardProps) {
   const [touchTimer, setTouchTimer] = useState<number | null>(null);
   const getCellStyle = (value: number): React.CSSProperties => {
-    if (value < 0) {
+    if (value === VOID_CELL) {
+      // 空洞（不属于棋盘）
+      return { backgroundColor: 'transparent', visibility: 'hidden' };
+    } else if (value < 0) {
       // 障碍块（负数ID）
       const pieceId = Math.abs(value);
       const color = PIECE_ID_TO_COLOR[pieceId];
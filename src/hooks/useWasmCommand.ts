@@ -38,7 +38,8 @@ export function useWasmCommand() {
       setLoading(true);
       setError(null);
       try {
-        const state = puzzle.newLevel(difficulty);
+        // 分片生成，困难关卡也不会卡住页面
+        const state = await puzzle.newLevelAsync(difficulty);
         return state;
       } catch (err) {
         const message = err instanceof Error ? err.message : String(err);
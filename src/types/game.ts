@@ -18,7 +18,7 @@ export interface Piece {
 }
 
 export interface Board {
-  cells: number[]; // 64个格子：负数(-1,-2,-3)=障碍块ID，0=空，正数(1-11)=已放置方块ID
+  cells: number[]; // 64个格子：负数=障碍块ID（取反），VOID_CELL=空洞，0=空，正数(1-11)=已放置方块ID
 }
 
 export interface GameState {
@@ -26,6 +26,7 @@ export interface GameState {
   pieces: Piece[];
   used_pieces: boolean[];
   obstacle_positions: Array<[number, number, number]>; // [row, col, piece_id]
+  clues: Placement[]; // 固定提示方块，玩家不能移动
 }
 
 export interface Placement {
@@ -40,6 +41,10 @@ export interface Solution {
   placements: Placement[];
 }
 
+export interface VerifiedSolution {
+  solution: Solution; // 从棋盘还原出的解（placements按方块ID排序）
+}
+
 export interface SolveResponse {
   no_solution: boolean;
   unique_solution: Solution | null;
@@ -48,6 +53,89 @@ export interface SolveResponse {
 
 export type Difficulty = 'easy' | 'medium' | 'hard';
 
+// 关卡包（难度标签使用Rust枚举名）
+export type PackDifficulty = 'Easy' | 'Medium' | 'Hard';
+
+export type UnlockRule = 'Always' | 'AfterPrevious' | { AfterSolvedCount: number };
+
+export interface PackLevel {
+  name: string;
+  level: GameState;
+  difficulty: PackDifficulty;
+  score: number;
+  unlock: UnlockRule;
+  best_time_ms: number | null;
+}
+
+export interface LevelPack {
+  metadata: { name: string; description: string; author: string; version: number };
+  levels: PackLevel[];
+}
+
+export interface PackLevelSummary {
+  index: number;
+  name: string;
+  difficulty: PackDifficulty;
+  score: number;
+  unlocked: boolean;
+  best_time_ms: number | null;
+}
+
+// 游戏会话（规则由Rust侧Session校验）
+export type MoveKind = 'Place' | 'Remove' | 'Rotate';
+
+export interface Move {
+  kind: MoveKind;
+  piece_id: number;
+  row: number;
+  col: number;
+  rotated: boolean;
+  at_ms: number; // 距离会话开始的毫秒数
+}
+
+export type ReplayOrder = 'solver' | 'reading' | 'human';
+
+export interface Replay {
+  level: GameState;
+  steps: Move[];
+  duration_ms: number;
+}
+
+export interface Progress {
+  placed_pieces: number;
+  total_pieces: number;
+  filled_cells: number;
+  total_cells: number;
+  elapsed_ms: number;
+  moves: number;
+  hints_used: number;
+  solved: boolean;
+}
+
+// 关卡生成统计（参数使用小写，返回值中使用Rust枚举名）
+export type GenerationStrategyParam = 'auto' | 'solution' | 'obstacles';
+export type GenerationStrategy = 'Auto' | 'FromSolution' | 'FromObstacles';
+
+export interface RejectionCounts {
+  not_unique: number;
+  no_solution: number;
+  placement_failed: number;
+  constraints_failed: number;
+}
+
+export interface GenerationReport {
+  requested: GenerationStrategy;
+  strategy: GenerationStrategy | null;
+  attempts: number;
+  rejections: RejectionCounts;
+  elapsed_ms: number;
+}
+
+export interface GenerationOutcome {
+  level: GameState | null;
+  report: GenerationReport;
+}
+
 // 游戏阶段
 export type GamePhase = 'placingObstacles' | 'playing' | 'completed';
 
@@ -81,6 +169,9 @@ export const COLOR_MAP: Record<Color, string> = {
   Gray2: '#95a5a6',   // 浅灰色
 };
 
+// 空洞格子（不属于棋盘，与障碍的负数ID区分）
+export const VOID_CELL = -128;
+
 // 方块ID到颜色的直接映射（用于Board组件快速查找）
 export const PIECE_ID_TO_COLOR: Record<number, Color> = {
   1: 'Black1',